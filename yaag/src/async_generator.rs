@@ -0,0 +1,56 @@
+use core::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+/// Asynchronous generator, polled for its items.
+///
+/// Same as the `Stream`/`AsyncIterator` with the `Option` and `Poll` swapped, which is the signature proposed for stdlib.
+pub trait AsyncGenerator {
+    /// Type of the yielded values.
+    type Yield;
+
+    /// Runs the generator until the next yield, until it completes, or until it awaits a future that isn't ready.
+    ///
+    /// Returns `None` once the generator completes, `Some(Poll::Pending)` when it waits for `cx` to be woken,
+    /// and `Some(Poll::Ready(item))` when it yields.
+    fn poll_generate(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Option<Poll<Self::Yield>>;
+}
+
+impl<G: AsyncGenerator + ?Sized> AsyncGenerator for Pin<&mut G> {
+    type Yield = G::Yield;
+
+    #[inline]
+    fn poll_generate(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Option<Poll<Self::Yield>> {
+        G::poll_generate(self.get_mut().as_mut(), cx)
+    }
+}
+
+impl<G: AsyncGenerator + Unpin + ?Sized> AsyncGenerator for &mut G {
+    type Yield = G::Yield;
+
+    #[inline]
+    fn poll_generate(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Option<Poll<Self::Yield>> {
+        G::poll_generate(Pin::new(&mut **self.get_mut()), cx)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<G: AsyncGenerator + ?Sized> AsyncGenerator for Pin<alloc::boxed::Box<G>> {
+    type Yield = G::Yield;
+
+    #[inline]
+    fn poll_generate(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Option<Poll<Self::Yield>> {
+        G::poll_generate(self.get_mut().as_mut(), cx)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<G: AsyncGenerator + Unpin + ?Sized> AsyncGenerator for alloc::boxed::Box<G> {
+    type Yield = G::Yield;
+
+    #[inline]
+    fn poll_generate(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Option<Poll<Self::Yield>> {
+        G::poll_generate(Pin::new(&mut **self.get_mut()), cx)
+    }
+}
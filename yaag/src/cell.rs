@@ -0,0 +1,37 @@
+use core::{cell::UnsafeCell, pin::Pin};
+
+/// Pinned generator's state machine, that shared references to the generator never reach.
+///
+/// Body holds `&mut` borrows of its own locals across the yields (like the pinned delegate of `yield from`),
+/// and a shared reference covering the state machine would invalidate these. `UnsafeCell` keeps
+/// `&Gn` (as in `is_complete`, `size_hint`) from asserting anything about its bytes.
+pub struct PinCell<F>(UnsafeCell<F>);
+
+// shared reference only ever reaches `F` as `&F`
+unsafe impl<F: Sync> Sync for PinCell<F> {}
+
+impl<F> PinCell<F> {
+    #[inline]
+    pub fn new(value: F) -> Self {
+        Self(UnsafeCell::new(value))
+    }
+
+    #[inline]
+    pub fn get_pin_mut(self: Pin<&mut Self>) -> Pin<&mut F> {
+        // value is pinned structurally
+        unsafe { self.map_unchecked_mut(|cell| cell.0.get_mut()) }
+    }
+
+    #[cfg(feature = "alloc")]
+    #[inline]
+    pub fn into_inner(self) -> F {
+        self.0.into_inner()
+    }
+}
+
+impl<F: Clone> Clone for PinCell<F> {
+    fn clone(&self) -> Self {
+        // no one is mutating the value, as long as there's a shared reference
+        Self::new(unsafe { &*self.0.get() }.clone())
+    }
+}
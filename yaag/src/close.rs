@@ -0,0 +1,25 @@
+use core::fmt;
+
+/// What `yield` of a `closable` generator evaluates to, once the generator is closed.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Cancelled;
+
+impl fmt::Display for Cancelled {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("generator was closed")
+    }
+}
+
+impl core::error::Error for Cancelled {}
+
+/// Error of closing the generator, that yielded again instead of finishing. Holds the yielded item.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct YieldedAfterClose<T>(pub T);
+
+impl<T> fmt::Display for YieldedAfterClose<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("generator yielded after being closed")
+    }
+}
+
+impl<T: fmt::Debug> core::error::Error for YieldedAfterClose<T> {}
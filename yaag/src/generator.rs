@@ -0,0 +1,209 @@
+use core::pin::Pin;
+
+use crate::GeneratorState;
+
+/// Resumable computation, yielding values until it completes with a value.
+///
+/// Same as the (unstable) `core::ops::Coroutine` without resume arguments.
+pub trait Generator {
+    /// Type of the yielded values.
+    type Yield;
+    /// Type of the completion value.
+    type Return;
+
+    /// Runs the generator until the next yield, or until it completes.
+    ///
+    /// Generators may panic, if resumed after completion.
+    fn resume(self: Pin<&mut Self>) -> GeneratorState<Self::Yield, Self::Return>;
+}
+
+impl<G: Generator + ?Sized> Generator for Pin<&mut G> {
+    type Yield = G::Yield;
+    type Return = G::Return;
+
+    #[inline]
+    fn resume(self: Pin<&mut Self>) -> GeneratorState<Self::Yield, Self::Return> {
+        G::resume(self.get_mut().as_mut())
+    }
+}
+
+impl<G: Generator + Unpin + ?Sized> Generator for &mut G {
+    type Yield = G::Yield;
+    type Return = G::Return;
+
+    #[inline]
+    fn resume(self: Pin<&mut Self>) -> GeneratorState<Self::Yield, Self::Return> {
+        G::resume(Pin::new(&mut **self.get_mut()))
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<G: Generator + ?Sized> Generator for Pin<alloc::boxed::Box<G>> {
+    type Yield = G::Yield;
+    type Return = G::Return;
+
+    #[inline]
+    fn resume(self: Pin<&mut Self>) -> GeneratorState<Self::Yield, Self::Return> {
+        G::resume(self.get_mut().as_mut())
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<G: Generator + Unpin + ?Sized> Generator for alloc::boxed::Box<G> {
+    type Yield = G::Yield;
+    type Return = G::Return;
+
+    #[inline]
+    fn resume(self: Pin<&mut Self>) -> GeneratorState<Self::Yield, Self::Return> {
+        G::resume(Pin::new(&mut **self.get_mut()))
+    }
+}
+
+/// Combinators for the [`Generator`]s.
+pub trait GeneratorExt: Generator {
+    /// Maps each yielded value with `f`, keeping the completion value.
+    fn map<U, F: FnMut(Self::Yield) -> U>(self, f: F) -> Map<Self, F>
+    where
+        Self: Sized,
+    {
+        Map { generator: self, f }
+    }
+
+    /// Yields only the values matching the `predicate`, keeping the completion value.
+    fn filter<P: FnMut(&Self::Yield) -> bool>(self, predicate: P) -> Filter<Self, P>
+    where
+        Self: Sized,
+    {
+        Filter {
+            generator: self,
+            predicate,
+        }
+    }
+
+    /// Yields values of this generator, and then the ones of the `other`. Completes with both completion values.
+    fn chain<B: Generator<Yield = Self::Yield>>(self, other: B) -> Chain<Self, B>
+    where
+        Self: Sized,
+    {
+        Chain {
+            first: self,
+            second: other,
+            first_ret: None,
+        }
+    }
+
+    /// Iterator over the yielded values. Completion value is discarded.
+    fn into_iter(self) -> IntoIter<Self>
+    where
+        Self: Sized + Unpin,
+    {
+        IntoIter {
+            generator: self,
+            complete: false,
+        }
+    }
+}
+
+impl<G: Generator + ?Sized> GeneratorExt for G {}
+
+/// Generator returned by [`GeneratorExt::map`].
+pub struct Map<G, F> {
+    generator: G,
+    f: F,
+}
+
+impl<G: Generator, U, F: FnMut(G::Yield) -> U> Generator for Map<G, F> {
+    type Yield = U;
+    type Return = G::Return;
+
+    fn resume(self: Pin<&mut Self>) -> GeneratorState<Self::Yield, Self::Return> {
+        // only generator is pinned
+        let self_ = unsafe { self.get_unchecked_mut() };
+        match unsafe { Pin::new_unchecked(&mut self_.generator) }.resume() {
+            GeneratorState::Yielded(item) => GeneratorState::Yielded((self_.f)(item)),
+            GeneratorState::Complete(ret) => GeneratorState::Complete(ret),
+        }
+    }
+}
+
+/// Generator returned by [`GeneratorExt::filter`].
+pub struct Filter<G, P> {
+    generator: G,
+    predicate: P,
+}
+
+impl<G: Generator, P: FnMut(&G::Yield) -> bool> Generator for Filter<G, P> {
+    type Yield = G::Yield;
+    type Return = G::Return;
+
+    fn resume(self: Pin<&mut Self>) -> GeneratorState<Self::Yield, Self::Return> {
+        // only generator is pinned
+        let self_ = unsafe { self.get_unchecked_mut() };
+        let mut generator = unsafe { Pin::new_unchecked(&mut self_.generator) };
+        loop {
+            match generator.as_mut().resume() {
+                GeneratorState::Yielded(item) if !(self_.predicate)(&item) => {}
+                state => return state,
+            }
+        }
+    }
+}
+
+/// Generator returned by [`GeneratorExt::chain`].
+pub struct Chain<A: Generator, B> {
+    first: A,
+    second: B,
+    /// Set once the first generator completes
+    first_ret: Option<A::Return>,
+}
+
+impl<A: Generator, B: Generator<Yield = A::Yield>> Generator for Chain<A, B> {
+    type Yield = A::Yield;
+    type Return = (A::Return, B::Return);
+
+    fn resume(self: Pin<&mut Self>) -> GeneratorState<Self::Yield, Self::Return> {
+        // generators are pinned, completion value is not
+        let self_ = unsafe { self.get_unchecked_mut() };
+        if self_.first_ret.is_none() {
+            match unsafe { Pin::new_unchecked(&mut self_.first) }.resume() {
+                GeneratorState::Yielded(item) => return GeneratorState::Yielded(item),
+                GeneratorState::Complete(ret) => self_.first_ret = Some(ret),
+            }
+        }
+        match unsafe { Pin::new_unchecked(&mut self_.second) }.resume() {
+            GeneratorState::Yielded(item) => GeneratorState::Yielded(item),
+            GeneratorState::Complete(ret) => GeneratorState::Complete((
+                self_
+                    .first_ret
+                    .take()
+                    .expect("generator resumed after completion"),
+                ret,
+            )),
+        }
+    }
+}
+
+/// Iterator returned by [`GeneratorExt::into_iter`].
+pub struct IntoIter<G> {
+    generator: G,
+    complete: bool,
+}
+
+impl<G: Generator + Unpin> Iterator for IntoIter<G> {
+    type Item = G::Yield;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.complete {
+            return None;
+        }
+        match Pin::new(&mut self.generator).resume() {
+            GeneratorState::Yielded(item) => Some(item),
+            GeneratorState::Complete(_) => {
+                self.complete = true;
+                None
+            }
+        }
+    }
+}
+
+impl<G: Generator + Unpin> core::iter::FusedIterator for IntoIter<G> {}
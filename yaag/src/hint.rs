@@ -0,0 +1,99 @@
+/// Size hint of the generator, updated as it yields.
+pub trait Hint {
+    fn size_hint(&self) -> (usize, Option<usize>);
+
+    /// Called on each item yielded.
+    fn yielded(&mut self);
+
+    /// Called when the generator code re-declares the hint with `size_hint!(..)`.
+    fn update(&mut self, lo: usize, hi: Option<usize>);
+
+    /// Called once the generator completes on its own, rather than being closed.
+    #[inline]
+    fn completed(&self) {}
+}
+
+/// Nothing is known about the number of items.
+impl Hint for () {
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, None)
+    }
+
+    #[inline]
+    fn yielded(&mut self) {}
+
+    #[inline]
+    fn update(&mut self, _lo: usize, _hi: Option<usize>) {}
+}
+
+/// Exact number of items left, declared with `len = n`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Len(pub usize);
+
+impl Hint for Len {
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.0, Some(self.0))
+    }
+
+    #[inline]
+    fn yielded(&mut self) {
+        debug_assert!(
+            self.0 > 0,
+            "generator yielded more items than its declared `len`"
+        );
+        self.0 = self.0.saturating_sub(1);
+    }
+
+    #[inline]
+    fn update(&mut self, lo: usize, hi: Option<usize>) {
+        debug_assert_eq!(Some(lo), hi, "declared `len` must be exact");
+        self.0 = lo;
+    }
+
+    #[inline]
+    fn completed(&self) {
+        debug_assert!(
+            self.0 == 0,
+            "generator completed with {} of its declared `len` items left",
+            self.0
+        );
+    }
+}
+
+/// Bounds on the number of items left, declared with `size_hint = (lo, hi)`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SizeHint(pub usize, pub Option<usize>);
+
+impl Hint for SizeHint {
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.0, self.1)
+    }
+
+    #[inline]
+    fn yielded(&mut self) {
+        debug_assert!(
+            self.1 != Some(0),
+            "generator yielded more items than the upper bound of its declared `size_hint`"
+        );
+        self.0 = self.0.saturating_sub(1);
+        self.1 = self.1.map(|hi| hi.saturating_sub(1));
+    }
+
+    #[inline]
+    fn update(&mut self, lo: usize, hi: Option<usize>) {
+        self.0 = lo;
+        self.1 = hi;
+    }
+
+    #[inline]
+    fn completed(&self) {
+        debug_assert!(
+            self.0 == 0,
+            "generator completed {} items short of the lower bound of its declared `size_hint`",
+            self.0
+        );
+    }
+}
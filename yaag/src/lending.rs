@@ -0,0 +1,37 @@
+use core::marker::PhantomData;
+
+/// Family of the types, lent by a lending generator: one type per lifetime of the borrow.
+pub trait Lend {
+    type Item<'a>
+    where
+        Self: 'a;
+}
+
+/// Family of `&'_ T`
+pub struct Ref<T: ?Sized>(PhantomData<T>);
+
+impl<T: ?Sized> Lend for Ref<T> {
+    type Item<'a>
+        = &'a T
+    where
+        Self: 'a;
+}
+
+/// Family of `&'_ mut T`
+pub struct Mut<T: ?Sized>(PhantomData<T>);
+
+impl<T: ?Sized> Lend for Mut<T> {
+    type Item<'a>
+        = &'a mut T
+    where
+        Self: 'a;
+}
+
+/// Like [`Iterator`], but items may borrow from the iterator itself, so only one of them can be alive at a time.
+pub trait LendingIterator {
+    type Item<'a>
+    where
+        Self: 'a;
+
+    fn next(&mut self) -> Option<Self::Item<'_>>;
+}
@@ -1,12 +1,44 @@
 #![cfg_attr(not(test), no_std)]
+#![cfg_attr(feature = "nightly", feature(coroutine_trait))]
+#![cfg_attr(feature = "async-iter", feature(async_iterator))]
 
-pub use yaag_proc_macro::gn;
+#[cfg(feature = "alloc")]
+extern crate alloc;
+#[cfg(feature = "tls")]
+extern crate std;
+
+pub use yaag_proc_macro::{generator, gn};
 
 #[doc(hidden)]
 pub mod sync;
+#[cfg(feature = "alloc")]
+pub use sync::{BoxedGn, LocalBoxedGn};
 
 #[doc(hidden)]
 #[cfg(feature = "async")]
 pub mod not_sync;
+#[cfg(all(feature = "async", feature = "alloc"))]
+pub use not_sync::{BoxedAsyncGn, LocalBoxedAsyncGn};
 
+mod cell;
+mod slot;
+#[cfg(not(feature = "tls"))]
 mod waker;
+
+mod state;
+pub use state::GeneratorState;
+
+mod close;
+pub use close::{Cancelled, YieldedAfterClose};
+
+mod hint;
+pub use hint::{Hint, Len, SizeHint};
+
+mod generator;
+pub use generator::{Chain, Filter, Generator, GeneratorExt, IntoIter, Map};
+
+mod async_generator;
+pub use async_generator::AsyncGenerator;
+
+mod lending;
+pub use lending::{Lend, LendingIterator, Mut, Ref};
@@ -1,14 +1,17 @@
 use core::{marker::PhantomData, pin::Pin, task::Context};
 
-use crate::not_sync::State;
+use crate::{
+    not_sync::{State, Token},
+    slot::{self, Id},
+};
 
-pub struct Await<F, O>(F, PhantomData<O>);
+pub struct Await<F, O>(F, Id, PhantomData<O>);
 
 impl<F, O> Await<F, O> {
-    /// SAFETY: **never** use this function.
     #[doc(hidden)]
-    pub unsafe fn ___make(f: F) -> Self {
-        Self(f, PhantomData)
+    #[inline]
+    pub fn ___make(token: &Token<O>, f: F) -> Self {
+        Self(f, token.0, PhantomData)
     }
 }
 
@@ -16,10 +19,19 @@ impl<F: Future, O> Future for Await<F, O> {
     type Output = F::Output;
 
     fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> core::task::Poll<Self::Output> {
-        let inner = unsafe { Pin::new_unchecked(&mut self.get_unchecked_mut().0) };
-        let state = unsafe { &mut *cx.waker().data().cast::<State<O>>().cast_mut() };
-        let waker = state.waker.clone();
-        let mut cx_inner = Context::from_waker(&waker);
-        inner.poll(&mut cx_inner)
+        // not read through `Deref`, that would retag the awaited future, borrowing its own fields
+        let this = unsafe { self.get_unchecked_mut() };
+        let id = this.1;
+        let inner = unsafe { Pin::new_unchecked(&mut this.0) };
+        let state = unsafe { &mut *slot::data(cx, id).cast::<State<O>>().cast_mut() };
+        state.awaited = true;
+        #[cfg(not(feature = "tls"))]
+        {
+            let waker = state.waker.clone();
+            inner.poll(&mut Context::from_waker(&waker))
+        }
+        // generator is polled with the real waker already
+        #[cfg(feature = "tls")]
+        inner.poll(cx)
     }
 }
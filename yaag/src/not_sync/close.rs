@@ -0,0 +1,67 @@
+use core::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use crate::{GeneratorState, Hint, YieldedAfterClose, not_sync::Gn};
+
+/// Future returned by [`Gn::close`], finishing the generator early.
+///
+/// `closable` generator suspended at a `yield` is resumed, with the `yield` evaluating to `Err(Cancelled)`,
+/// and polled until it returns, so that its cleanup may await. Any other generator is just dropped.
+/// Resolves to the completion value, if the generator has returned it now.
+///
+/// Resolves to [`YieldedAfterClose`] if the generator yields instead of returning. It's dropped then.
+pub struct Close<'a, F: Future<Output = R>, O, R, H> {
+    gn: Pin<&'a mut Gn<F, O, R, H>>,
+    /// Set once the generator is resumed with the cancellation
+    cancelling: bool,
+}
+
+impl<'a, F: Future<Output = R>, O, R, H> Close<'a, F, O, R, H> {
+    #[inline]
+    pub(super) fn new(gn: Pin<&'a mut Gn<F, O, R, H>>) -> Self {
+        Self {
+            gn,
+            cancelling: false,
+        }
+    }
+
+    /// Drops the generator's future
+    fn finish(&mut self) {
+        // dropped in place
+        let gn = unsafe { self.gn.as_mut().get_unchecked_mut() };
+        unsafe { Pin::new_unchecked(&mut gn.fut) }.set(None);
+    }
+}
+
+impl<F: Future<Output = R>, O, R, H: Hint> Future for Close<'_, F, O, R, H> {
+    type Output = Result<Option<R>, YieldedAfterClose<O>>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        // pinned generator is behind a reference
+        let this = self.get_mut();
+        if !this.cancelling {
+            let gn = &this.gn;
+            // nothing to run, or no `yield` to return `Cancelled` from
+            if gn.is_complete() || gn.is_poisoned() || !gn.closable || !gn.at_yield {
+                this.finish();
+                return Poll::Ready(Ok(None));
+            }
+            this.cancelling = true;
+        }
+        if this.gn.is_complete() || this.gn.is_poisoned() {
+            // body panicked during the cleanup, or close is polled after it has resolved
+            this.finish();
+            return Poll::Ready(Ok(None));
+        }
+        match this.gn.as_mut().poll_state(cx, true) {
+            Poll::Pending => Poll::Pending,
+            Poll::Ready(GeneratorState::Complete(ret)) => Poll::Ready(Ok(Some(ret))),
+            Poll::Ready(GeneratorState::Yielded(item)) => {
+                this.finish();
+                Poll::Ready(Err(YieldedAfterClose(item)))
+            }
+        }
+    }
+}
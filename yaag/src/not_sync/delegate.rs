@@ -0,0 +1,207 @@
+use core::{
+    cell::Cell,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+#[cfg(feature = "futures")]
+use futures_core::Stream;
+
+use crate::{AsyncGenerator, GeneratorState, Hint, not_sync::Gn};
+
+/// Something `yield from` can delegate to.
+pub trait Delegate {
+    type Yield;
+    type Return;
+
+    fn poll_resume(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<GeneratorState<Self::Yield, Self::Return>>;
+}
+
+impl<D: Delegate + ?Sized> Delegate for Pin<&mut D> {
+    type Yield = D::Yield;
+    type Return = D::Return;
+
+    #[inline]
+    fn poll_resume(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<GeneratorState<Self::Yield, Self::Return>> {
+        D::poll_resume(self.get_mut().as_mut(), cx)
+    }
+}
+
+impl<F: Future<Output = R>, O, R, H: Hint> Delegate for Gn<F, O, R, H> {
+    type Yield = O;
+    type Return = R;
+
+    #[inline]
+    fn poll_resume(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<GeneratorState<Self::Yield, Self::Return>> {
+        Gn::poll_resume(self, cx)
+    }
+}
+
+pub struct Iter<I>(I);
+
+// iterator is never pinned
+impl<I> Unpin for Iter<I> {}
+
+impl<I: Iterator> Delegate for Iter<I> {
+    type Yield = I::Item;
+    type Return = ();
+
+    #[inline]
+    fn poll_resume(
+        self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+    ) -> Poll<GeneratorState<Self::Yield, Self::Return>> {
+        Poll::Ready(match self.get_mut().0.next() {
+            Some(item) => GeneratorState::Yielded(item),
+            None => GeneratorState::Complete(()),
+        })
+    }
+}
+
+pub struct FromGn<G>(G);
+
+impl<G: AsyncGenerator> Delegate for FromGn<G> {
+    type Yield = G::Yield;
+    type Return = ();
+
+    #[inline]
+    fn poll_resume(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<GeneratorState<Self::Yield, Self::Return>> {
+        // generator is pinned structurally
+        let generator = unsafe { self.map_unchecked_mut(|s| &mut s.0) };
+        match generator.poll_generate(cx) {
+            Some(Poll::Ready(item)) => Poll::Ready(GeneratorState::Yielded(item)),
+            Some(Poll::Pending) => Poll::Pending,
+            None => Poll::Ready(GeneratorState::Complete(())),
+        }
+    }
+}
+
+#[cfg(feature = "futures")]
+pub struct FromStream<S>(S);
+
+#[cfg(feature = "futures")]
+impl<S: Stream> Delegate for FromStream<S> {
+    type Yield = S::Item;
+    type Return = ();
+
+    #[inline]
+    fn poll_resume(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<GeneratorState<Self::Yield, Self::Return>> {
+        // stream is pinned structurally
+        let stream = unsafe { self.map_unchecked_mut(|s| &mut s.0) };
+        stream.poll_next(cx).map(|item| match item {
+            Some(item) => GeneratorState::Yielded(item),
+            None => GeneratorState::Complete(()),
+        })
+    }
+}
+
+/// Picks the most suitable [`Delegate`] for the value (generators with completion values are preferred over the other async generators,
+/// these over streams, and streams over iterators)
+pub struct Wrap<T>(Cell<Option<T>>);
+
+impl<T> Wrap<T> {
+    #[inline]
+    pub fn new(value: T) -> Self {
+        Self(Cell::new(Some(value)))
+    }
+
+    #[inline]
+    fn take(&self) -> T {
+        self.0.take().expect("value was already delegated to")
+    }
+}
+
+pub trait ViaDelegate {
+    type Delegate: Delegate;
+
+    fn ___delegate(&self) -> Self::Delegate;
+}
+
+impl<D: Delegate> ViaDelegate for &&&Wrap<D> {
+    type Delegate = D;
+
+    #[inline]
+    fn ___delegate(&self) -> Self::Delegate {
+        self.take()
+    }
+}
+
+pub trait ViaGn {
+    type Delegate: Delegate;
+
+    fn ___delegate(&self) -> Self::Delegate;
+}
+
+impl<G: AsyncGenerator> ViaGn for &&Wrap<G> {
+    type Delegate = FromGn<G>;
+
+    #[inline]
+    fn ___delegate(&self) -> Self::Delegate {
+        FromGn(self.take())
+    }
+}
+
+pub trait ViaStream {
+    type Delegate: Delegate;
+
+    fn ___delegate(&self) -> Self::Delegate;
+}
+
+#[cfg(feature = "futures")]
+impl<S: Stream> ViaStream for &Wrap<S> {
+    type Delegate = FromStream<S>;
+
+    #[inline]
+    fn ___delegate(&self) -> Self::Delegate {
+        FromStream(self.take())
+    }
+}
+
+pub trait ViaIter {
+    type Delegate: Delegate;
+
+    fn ___delegate(&self) -> Self::Delegate;
+}
+
+impl<I: IntoIterator> ViaIter for Wrap<I> {
+    type Delegate = Iter<I::IntoIter>;
+
+    #[inline]
+    fn ___delegate(&self) -> Self::Delegate {
+        Iter(self.take().into_iter())
+    }
+}
+
+/// Future resuming the delegate once
+pub struct Resume<'a, D: ?Sized>(Pin<&'a mut D>);
+
+impl<'a, D: ?Sized> Resume<'a, D> {
+    #[inline]
+    pub fn new(delegate: Pin<&'a mut D>) -> Self {
+        Self(delegate)
+    }
+}
+
+impl<D: Delegate + ?Sized> Future for Resume<'_, D> {
+    type Output = GeneratorState<D::Yield, D::Return>;
+
+    #[inline]
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.0.as_mut().poll_resume(cx)
+    }
+}
@@ -1,60 +1,304 @@
-use crate::waker::make;
+use crate::{
+    AsyncGenerator, GeneratorState, Hint,
+    cell::PinCell,
+    slot::{self, Id},
+};
 use core::{
     marker::PhantomData,
     pin::Pin,
-    task::{Context, Poll, Waker},
+    task::{Context, Poll},
 };
 
 #[macro_export]
 macro_rules! async_gn_type {
     ($t:ty) => {
-        $crate::not_sync::Gn<impl ::core::future::Future<Output = ()>, $t>
+        $crate::async_gn_type!($t, ())
+    };
+    ($t:ty, $r:ty) => {
+        $crate::async_gn_type!($t, $r, ())
+    };
+    ($t:ty, $r:ty, $h:ty) => {
+        $crate::not_sync::Gn<impl ::core::future::Future<Output = $r>, $t, $r, $h>
     };
 }
 
 mod yld;
-use futures_core::Stream;
-pub use yld::Yield;
+pub use yld::{ClosableYield, SetHint, Yield};
 
 mod awt;
 pub use awt::Await;
 
+mod output;
+pub use output::IntoOutput;
+
+mod close;
+pub use close::Close;
+
+mod delegate;
+pub use delegate::{Delegate, Resume, ViaDelegate, ViaGn, ViaIter, ViaStream, Wrap};
+
+/// Generator's future got pending on its own, so it wasn't generated by `gn!`
+const PENDING_ERROR: &str =
+    "async generator's future is pending, but it neither yielded nor awaited";
+
+/// Proof that the code belongs to an async generator yielding `O`.
+///
+/// Only [`Gn::___new`] creates these, so that yields and awaits of the generated code need no `unsafe`.
+pub struct Token<O>(Id, PhantomData<fn(O) -> O>);
+
+#[repr(C)]
 struct State<O> {
+    /// Must be the first field, see [`crate::slot`]
+    pub id: Id,
     pub out: Option<O>,
-    pub waker: Waker,
+    /// Real waker, for the awaited futures
+    #[cfg(not(feature = "tls"))]
+    pub waker: core::task::Waker,
+    /// Set by [`Await`], as the awaited future may be pending without yielding anything
+    pub awaited: bool,
+    /// Generator is being closed, see [`Gn::close`]
+    pub cancelled: bool,
+    /// Set by [`SetHint`], for the generator to update its hint with
+    pub hint: Option<(usize, Option<usize>)>,
+}
+
+pub struct Gn<F: Future<Output = R>, O, R = (), H = ()> {
+    /// `None` once the generator completes
+    fut: Option<PinCell<F>>,
+    /// Id of the tokens handed to the future
+    id: Id,
+    /// Completion value, until it's taken
+    output: Option<R>,
+    /// Set while the future is polled, so it stays set if the body panics
+    poisoned: bool,
+    /// Whether the generator was built with `closable`, so its yields evaluate to `Result<(), Cancelled>`
+    closable: bool,
+    /// Whether the generator is suspended at a `yield` (rather than at an `await`, or not started)
+    at_yield: bool,
+    hint: H,
+    _ph: PhantomData<O>,
 }
 
-pub struct Gn<F: Future<Output = ()>, O> {
-    pub fut: F,
-    pub _ph: PhantomData<O>,
+impl<F: Future<Output = R>, O, R, H> Gn<F, O, R, H> {
+    /// Creates the generator from the future built by `make`.
+    ///
+    /// Only `gn!` should use this function.
+    #[doc(hidden)]
+    #[inline]
+    pub fn ___new(make: impl FnOnce(Token<O>) -> F, hint: H) -> Self {
+        let id = Id::next();
+        Self {
+            fut: Some(PinCell::new(make(Token(id, PhantomData)))),
+            id,
+            output: None,
+            poisoned: false,
+            closable: false,
+            at_yield: false,
+            hint,
+            _ph: PhantomData,
+        }
+    }
+
+    /// Same as [`Gn::___new`], for the generator that uses [`ClosableYield`].
+    ///
+    /// Only `gn!` should use this function.
+    #[doc(hidden)]
+    #[inline]
+    pub fn ___new_closable(make: impl FnOnce(Token<O>) -> F, hint: H) -> Self {
+        Self {
+            closable: true,
+            ..Self::___new(make, hint)
+        }
+    }
 }
 
-impl<F: Future<Output = ()>, O> Gn<F, O> {
-    // signature is based on the one proposed for stdlib
-    fn gn_poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Option<Poll<O>> {
-        let fut;
-        unsafe {
-            let self_ = self.as_mut().get_unchecked_mut();
-            fut = Pin::new_unchecked(&mut self_.fut);
+// future is the only field that's pinned
+impl<F: Future<Output = R> + Unpin, O, R, H> Unpin for Gn<F, O, R, H> {}
+
+/// Heap-pinned async generator, with its future type erased, that stays `Send`.
+#[cfg(feature = "alloc")]
+pub type BoxedAsyncGn<'a, O, R = (), H = ()> =
+    Gn<Pin<alloc::boxed::Box<dyn Future<Output = R> + Send + 'a>>, O, R, H>;
+
+/// Heap-pinned async generator, with its future type erased, that's not `Send`.
+#[cfg(feature = "alloc")]
+pub type LocalBoxedAsyncGn<'a, O, R = (), H = ()> =
+    Gn<Pin<alloc::boxed::Box<dyn Future<Output = R> + 'a>>, O, R, H>;
+
+#[cfg(feature = "alloc")]
+impl<F: Future<Output = R>, O, R, H> Gn<F, O, R, H> {
+    /// Moves generator's future to the heap, so that the generator is [`Unpin`] and has a nameable type.
+    pub fn boxed<'a>(self) -> BoxedAsyncGn<'a, O, R, H>
+    where
+        F: Send + 'a,
+    {
+        self.map_fut(|fut| alloc::boxed::Box::pin(fut) as _)
+    }
+
+    /// Same as [`Gn::boxed`], for the generators that aren't `Send`.
+    pub fn boxed_local<'a>(self) -> LocalBoxedAsyncGn<'a, O, R, H>
+    where
+        F: 'a,
+    {
+        self.map_fut(|fut| alloc::boxed::Box::pin(fut) as _)
+    }
+
+    fn map_fut<G: Future<Output = R>>(self, f: impl FnOnce(F) -> G) -> Gn<G, O, R, H> {
+        Gn {
+            fut: self.fut.map(|fut| PinCell::new(f(fut.into_inner()))),
+            id: self.id,
+            output: self.output,
+            poisoned: self.poisoned,
+            closable: self.closable,
+            at_yield: self.at_yield,
+            hint: self.hint,
+            _ph: PhantomData,
         }
+    }
+}
+
+impl<F: Future<Output = R>, O, R, H: Hint> Gn<F, O, R, H> {
+    /// Polls the generator's future once. Generator must be neither completed, nor poisoned.
+    ///
+    /// `cancelled` makes the pending `yield` of a closable generator evaluate to `Err(Cancelled)`.
+    fn poll_state(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        cancelled: bool,
+    ) -> Poll<GeneratorState<O, R>> {
+        let self_ = unsafe { self.get_unchecked_mut() };
+        let mut slot = unsafe { Pin::new_unchecked(&mut self_.fut) };
+        let fut = slot
+            .as_mut()
+            .as_pin_mut()
+            .expect("generator is not completed")
+            .get_pin_mut();
         let mut state = State {
+            id: self_.id,
             out: None,
+            #[cfg(not(feature = "tls"))]
             waker: cx.waker().clone(),
+            awaited: false,
+            cancelled,
+            hint: None,
         };
-        let waker = make((&raw mut state).cast_const().cast());
-        let poll = fut.poll(&mut Context::from_waker(&waker));
+        self_.poisoned = true;
+        self_.at_yield = false;
+        let poll = slot::poll_with(
+            (&raw mut state).cast_const().cast(),
+            Some(cx.waker()),
+            |cx| fut.poll(cx),
+        );
+        self_.poisoned = false;
         // no more references to `state` exist at this point
+        if let Some((lo, hi)) = state.hint {
+            self_.hint.update(lo, hi);
+        }
         match poll {
-            core::task::Poll::Ready(()) => {
-                // finished generation, nothing to return
-                None
+            Poll::Ready(ret) => {
+                // finished generation, the state machine is of no use anymore
+                slot.set(None);
+                // generator is complete already, if the check panics
+                if !cancelled {
+                    self_.hint.completed();
+                }
+                Poll::Ready(GeneratorState::Complete(ret))
             }
-            core::task::Poll::Pending => {
-                // item was maybe-saved in the output
-                Some(match state.out {
-                    Some(item) => Poll::Ready(item),
-                    None => Poll::Pending,
-                })
+            // item was maybe-saved in the output
+            Poll::Pending => match state.out {
+                Some(item) => {
+                    self_.at_yield = true;
+                    self_.hint.yielded();
+                    Poll::Ready(GeneratorState::Yielded(item))
+                }
+                None if state.awaited => Poll::Pending,
+                None => panic!("{PENDING_ERROR}"),
+            },
+        }
+    }
+
+    /// Polls the generator until its next yield, or until it completes with a value.
+    ///
+    /// # Panics
+    ///
+    /// If the generator has already completed (see [`Gn::is_complete`]), or if its body panicked before (see [`Gn::is_poisoned`]).
+    pub fn poll_resume(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<GeneratorState<O, R>> {
+        if self.poisoned {
+            panic!("generator resumed after its body panicked");
+        }
+        if self.is_complete() {
+            panic!("generator resumed after completion");
+        }
+        self.poll_state(cx, false)
+    }
+
+    /// Polls the generator for its next item, `Poll::Ready(None)` meaning it has finished (same as `Stream::poll_next`).
+    ///
+    /// Completion value is kept, see [`Gn::take_output`].
+    #[inline]
+    pub fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<O>> {
+        transpose(self.poll_generate(cx))
+    }
+
+    /// Completion value of the generator, that has finished through [`Gn::poll_next`] (or `Stream`).
+    ///
+    /// `None` until then, and after the value is taken.
+    #[inline]
+    pub fn take_output(self: Pin<&mut Self>) -> Option<R> {
+        // completion value is never pinned
+        unsafe { self.get_unchecked_mut() }.output.take()
+    }
+
+    /// Future skipping the rest of the items, and resolving to the completion value.
+    #[inline]
+    pub fn into_output(self) -> IntoOutput<F, O, R, H> {
+        IntoOutput::new(self)
+    }
+
+    /// Future finishing the generator early, see [`Close`].
+    #[inline]
+    pub fn close(self: Pin<&mut Self>) -> Close<'_, F, O, R, H> {
+        Close::new(self)
+    }
+
+    /// Whether the generator has finished.
+    #[inline]
+    pub fn is_complete(&self) -> bool {
+        self.fut.is_none()
+    }
+
+    /// Whether the generator's body panicked. Poisoned generator is never polled again, and ends the stream.
+    #[inline]
+    pub fn is_poisoned(&self) -> bool {
+        self.poisoned
+    }
+
+    /// Bounds on the number of items left, as declared in the generator header.
+    #[inline]
+    pub fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.is_complete() || self.is_poisoned() {
+            (0, Some(0))
+        } else {
+            self.hint.size_hint()
+        }
+    }
+}
+
+impl<F: Future<Output = R>, O, R, H: Hint> AsyncGenerator for Gn<F, O, R, H> {
+    type Yield = O;
+
+    fn poll_generate(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Option<Poll<O>> {
+        // poisoned generator is not polled again, and ends the stream; completed one stays completed
+        if self.is_poisoned() || self.is_complete() {
+            return None;
+        }
+        match self.as_mut().poll_state(cx, false) {
+            Poll::Pending => Some(Poll::Pending),
+            Poll::Ready(GeneratorState::Yielded(item)) => Some(Poll::Ready(item)),
+            Poll::Ready(GeneratorState::Complete(ret)) => {
+                // completion value is never pinned
+                unsafe { self.get_unchecked_mut() }.output = Some(ret);
+                None
             }
         }
     }
@@ -69,11 +313,40 @@ fn transpose<T>(value: Option<Poll<T>>) -> Poll<Option<T>> {
     }
 }
 
-impl<F: Future<Output = ()>, O> Stream for Gn<F, O> {
+#[cfg(feature = "futures")]
+impl<F: Future<Output = R>, O, R, H: Hint> futures_core::Stream for Gn<F, O, R, H> {
     type Item = O;
 
     #[inline]
     fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
-        transpose(self.gn_poll_next(cx))
+        Gn::poll_next(self, cx)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        Gn::size_hint(self)
+    }
+}
+
+#[cfg(feature = "futures")]
+impl<F: Future<Output = R>, O, R, H: Hint> futures_core::FusedStream for Gn<F, O, R, H> {
+    #[inline]
+    fn is_terminated(&self) -> bool {
+        self.is_complete() || self.is_poisoned()
+    }
+}
+
+#[cfg(feature = "async-iter")]
+impl<F: Future<Output = R>, O, R, H: Hint> core::async_iter::AsyncIterator for Gn<F, O, R, H> {
+    type Item = O;
+
+    #[inline]
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        Gn::poll_next(self, cx)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        Gn::size_hint(self)
     }
 }
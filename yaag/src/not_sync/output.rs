@@ -0,0 +1,41 @@
+use core::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use crate::{AsyncGenerator, Hint, not_sync::Gn};
+
+/// Future returned by [`Gn::into_output`].
+pub struct IntoOutput<F: Future<Output = R>, O, R, H>(Gn<F, O, R, H>);
+
+impl<F: Future<Output = R>, O, R, H> IntoOutput<F, O, R, H> {
+    #[inline]
+    pub(super) fn new(gn: Gn<F, O, R, H>) -> Self {
+        Self(gn)
+    }
+}
+
+impl<F: Future<Output = R>, O, R, H: Hint> Future for IntoOutput<F, O, R, H> {
+    type Output = R;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        // generator is pinned structurally
+        let mut gn = unsafe { self.map_unchecked_mut(|s| &mut s.0) };
+        if gn.is_poisoned() {
+            panic!("generator resumed after its body panicked");
+        }
+        loop {
+            match gn.as_mut().poll_generate(cx) {
+                Some(Poll::Pending) => return Poll::Pending,
+                // remaining items are skipped
+                Some(Poll::Ready(_)) => {}
+                None => {
+                    return Poll::Ready(
+                        gn.take_output()
+                            .expect("generator's completion value was already taken"),
+                    );
+                }
+            }
+        }
+    }
+}
@@ -1,17 +1,22 @@
 use core::{
+    marker::PhantomData,
     pin::Pin,
     task::{Context, Poll},
 };
 
-use crate::not_sync::State;
+use crate::{
+    Cancelled,
+    not_sync::{State, Token},
+    slot::{self, Id},
+};
 
-pub struct Yield<O>(Option<O>);
+pub struct Yield<O>(Id, Option<O>);
 
 impl<O> Yield<O> {
-    /// SAFETY: **never** use this function.
     #[doc(hidden)]
-    pub unsafe fn ___make(o: O) -> Self {
-        Self(Some(o))
+    #[inline]
+    pub fn ___make(token: &Token<O>, o: O) -> Self {
+        Self(token.0, Some(o))
     }
 }
 
@@ -19,8 +24,9 @@ impl<O> Future for Yield<O> {
     type Output = ();
 
     fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> core::task::Poll<Self::Output> {
-        if let Some(data) = unsafe { self.get_unchecked_mut().0.take() } {
-            let state = unsafe { &mut *cx.waker().data().cast::<State<O>>().cast_mut() };
+        let id = self.0;
+        if let Some(data) = unsafe { self.get_unchecked_mut().1.take() } {
+            let state = unsafe { &mut *slot::data(cx, id).cast::<State<O>>().cast_mut() };
             state.out = Some(data);
             Poll::Pending
         } else {
@@ -28,3 +34,49 @@ impl<O> Future for Yield<O> {
         }
     }
 }
+
+/// `yield` of a `closable` generator, evaluating to [`Cancelled`] once the generator is closed
+pub struct ClosableYield<O>(Yield<O>);
+
+impl<O> ClosableYield<O> {
+    #[doc(hidden)]
+    #[inline]
+    pub fn ___make(token: &Token<O>, o: O) -> Self {
+        Self(Yield::___make(token, o))
+    }
+}
+
+impl<O> Future for ClosableYield<O> {
+    type Output = Result<(), Cancelled>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> core::task::Poll<Self::Output> {
+        let state = unsafe { &*slot::data(cx, self.0.0).cast::<State<O>>() };
+        if state.cancelled && self.0.1.is_none() {
+            return Poll::Ready(Err(Cancelled));
+        }
+        // yield itself is never pinned
+        let yld = unsafe { self.map_unchecked_mut(|s| &mut s.0) };
+        yld.poll(cx).map(Ok)
+    }
+}
+
+/// `size_hint!(..)`, handing the re-declared hint to the generator
+pub struct SetHint<O>(Id, (usize, Option<usize>), PhantomData<O>);
+
+impl<O> SetHint<O> {
+    #[doc(hidden)]
+    #[inline]
+    pub fn ___make(token: &Token<O>, hint: (usize, Option<usize>)) -> Self {
+        Self(token.0, hint, PhantomData)
+    }
+}
+
+impl<O> Future for SetHint<O> {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> core::task::Poll<Self::Output> {
+        let state = unsafe { &mut *slot::data(cx, self.0).cast::<State<O>>().cast_mut() };
+        state.hint = Some(self.1);
+        Poll::Ready(())
+    }
+}
@@ -0,0 +1,113 @@
+//! How generator's state reaches the `Yield` and `Await` futures polled inside of it.
+//!
+//! By default, the pointer to it is smuggled through the [`Waker`] data (see [`crate::waker`]).
+//! With `tls` feature, it's stored in a thread-local for the duration of the poll instead,
+//! so the future is polled with the caller's own waker.
+//!
+//! Either way, the data is a generator's state, that starts with its [`Id`] (states are `#[repr(C)]` for that).
+//! Tokens carry the same id, so that a token smuggled into another generator is caught before its state is touched.
+
+use core::{
+    sync::atomic::{AtomicUsize, Ordering},
+    task::{Context, Waker},
+};
+
+/// Identity of a generator instance, shared by its state and its token
+#[doc(hidden)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Id(usize);
+
+impl Id {
+    /// Id, that no other generator has.
+    ///
+    /// Ids are never reused, as a reused one would let a token through to the wrong generator:
+    /// once all of them are handed out (after 2^32 generators on 32-bit targets), this panics, and keeps panicking.
+    #[inline]
+    pub fn next() -> Self {
+        static NEXT: AtomicUsize = AtomicUsize::new(0);
+        // counter stays at `usize::MAX` once it gets there, instead of wrapping around
+        let id = NEXT
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |id| id.checked_add(1))
+            .expect("yaag ran out of generator ids");
+        Self(id)
+    }
+}
+
+/// Retrieves the data passed to [`poll_with`], that must be the state of the generator `id`.
+///
+/// Panics if the future is polled by anything but its own generator.
+#[inline]
+pub fn data(cx: &Context<'_>, id: Id) -> *const () {
+    let data = self::raw_data(cx);
+    // every state starts with its generator's id, whatever the rest of it is
+    assert!(
+        unsafe { *data.cast::<Id>() } == id,
+        "yaag's internal future was polled by another generator; tokens must not leave the generator code they were given to"
+    );
+    data
+}
+
+/// Polls with `data` reachable through [`data`] from within `poll`.
+///
+/// `waker` is the one generator itself was polled with (if any).
+#[cfg(not(feature = "tls"))]
+#[inline]
+pub fn poll_with<T>(
+    data: *const (),
+    _waker: Option<&Waker>,
+    poll: impl FnOnce(&mut Context<'_>) -> T,
+) -> T {
+    let waker = crate::waker::make(data);
+    poll(&mut Context::from_waker(&waker))
+}
+
+/// Retrieves the data passed to [`poll_with`].
+///
+/// Panics if the future is polled by anything but a generator.
+#[cfg(not(feature = "tls"))]
+#[inline]
+fn raw_data(cx: &Context<'_>) -> *const () {
+    crate::waker::data(cx.waker())
+}
+
+#[cfg(feature = "tls")]
+std::thread_local! {
+    static SLOT: core::cell::Cell<*const ()> = const { core::cell::Cell::new(core::ptr::null()) };
+}
+
+/// Polls with `data` reachable through [`data`] from within `poll`.
+///
+/// `waker` is the one generator itself was polled with (if any).
+#[cfg(feature = "tls")]
+#[inline]
+pub fn poll_with<T>(
+    data: *const (),
+    waker: Option<&Waker>,
+    poll: impl FnOnce(&mut Context<'_>) -> T,
+) -> T {
+    /// Restores the outer generator's data, even if `poll` panics
+    struct Restore(*const ());
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            SLOT.set(self.0);
+        }
+    }
+
+    let _restore = Restore(SLOT.replace(data));
+    poll(&mut Context::from_waker(waker.unwrap_or(Waker::noop())))
+}
+
+/// Retrieves the data passed to [`poll_with`].
+///
+/// Panics if the future is polled by anything but a generator.
+#[cfg(feature = "tls")]
+#[inline]
+fn raw_data(_: &Context<'_>) -> *const () {
+    let data = SLOT.get();
+    assert!(
+        !data.is_null(),
+        "yaag's internal future was polled outside of its generator; `Yield` and `Await` must only be awaited inside of the generator code"
+    );
+    data
+}
@@ -0,0 +1,8 @@
+/// Result of a single generator resumption.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GeneratorState<Y, R> {
+    /// Generator has suspended with a value.
+    Yielded(Y),
+    /// Generator has finished with a value.
+    Complete(R),
+}
@@ -0,0 +1,67 @@
+use core::{cell::Cell, pin::Pin};
+
+use crate::{Generator, GeneratorState};
+
+/// Iterator delegated to, as a generator completing with `()`
+pub struct Iter<I>(I);
+
+// iterator is never pinned
+impl<I> Unpin for Iter<I> {}
+
+impl<I: Iterator> Generator for Iter<I> {
+    type Yield = I::Item;
+    type Return = ();
+
+    #[inline]
+    fn resume(self: Pin<&mut Self>) -> GeneratorState<Self::Yield, Self::Return> {
+        match self.get_mut().0.next() {
+            Some(item) => GeneratorState::Yielded(item),
+            None => GeneratorState::Complete(()),
+        }
+    }
+}
+
+/// Value being delegated to. `yield from` takes it by autoref, so that generators are preferred over iterators
+pub struct Wrap<T>(Cell<Option<T>>);
+
+impl<T> Wrap<T> {
+    #[inline]
+    pub fn new(value: T) -> Self {
+        Self(Cell::new(Some(value)))
+    }
+
+    #[inline]
+    fn take(&self) -> T {
+        self.0.take().expect("value was already delegated to")
+    }
+}
+
+pub trait ViaGn {
+    type Delegate: Generator;
+
+    fn ___delegate(&self) -> Self::Delegate;
+}
+
+impl<G: Generator> ViaGn for &Wrap<G> {
+    type Delegate = G;
+
+    #[inline]
+    fn ___delegate(&self) -> Self::Delegate {
+        self.take()
+    }
+}
+
+pub trait ViaIter {
+    type Delegate: Generator;
+
+    fn ___delegate(&self) -> Self::Delegate;
+}
+
+impl<I: IntoIterator> ViaIter for Wrap<I> {
+    type Delegate = Iter<I::IntoIter>;
+
+    #[inline]
+    fn ___delegate(&self) -> Self::Delegate {
+        Iter(self.take().into_iter())
+    }
+}
@@ -0,0 +1,156 @@
+use core::{
+    marker::PhantomData,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use super::PENDING_ERROR;
+use crate::{
+    Lend, LendingIterator,
+    cell::PinCell,
+    slot::{self, Id},
+};
+
+#[macro_export]
+macro_rules! lending_gn_type {
+    ($l:ty) => {
+        $crate::sync::LendingGn<impl ::core::future::Future<Output = ()>, $l>
+    };
+}
+
+/// Proof that the code belongs to a generator lending the `L` family (see [`Token`](super::Token)).
+pub struct LendToken<L>(Id, PhantomData<fn(L) -> L>);
+
+/// Slot for the lent item
+#[repr(C)]
+struct State<T> {
+    /// Must be the first field, see [`crate::slot`]
+    id: Id,
+    out: Option<T>,
+}
+
+pub struct LendYield<'b, L: Lend + 'b>(Id, Option<L::Item<'b>>);
+
+// item is never pinned
+impl<'b, L: Lend + 'b> Unpin for LendYield<'b, L> {}
+
+impl<'b, L: Lend + 'b> LendYield<'b, L> {
+    #[doc(hidden)]
+    #[inline]
+    pub fn ___make(token: &LendToken<L>, item: L::Item<'b>) -> Self {
+        Self(token.0, Some(item))
+    }
+}
+
+impl<'b, L: Lend + 'b> Future for LendYield<'b, L> {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let self_ = self.get_mut();
+        if let Some(item) = self_.1.take() {
+            // state is `State<L::Item<'_>>` for some other lifetime, but lifetimes don't affect the layout.
+            // borrowed value outlives the state, as it stays borrowed until this future is polled again
+            let state = slot::data(cx, self_.0)
+                .cast::<State<L::Item<'b>>>()
+                .cast_mut();
+            unsafe { (*state).out = Some(item) };
+            Poll::Pending
+        } else {
+            Poll::Ready(())
+        }
+    }
+}
+
+/// Generator lending items, that borrow from its own state.
+pub struct LendingGn<F: Future<Output = ()>, L: Lend> {
+    /// `None` once the generator completes
+    fut: Option<PinCell<F>>,
+    /// Id of the tokens handed to the future
+    id: Id,
+    /// Set while the future is polled, so it stays set if the body panics
+    poisoned: bool,
+    _ph: PhantomData<L>,
+}
+
+// future is the only field that's pinned
+impl<F: Future<Output = ()> + Unpin, L: Lend> Unpin for LendingGn<F, L> {}
+
+impl<F: Future<Output = ()>, L: Lend> LendingGn<F, L> {
+    /// Creates the generator from the future built by `make`.
+    ///
+    /// Only `gn!` should use this function.
+    #[doc(hidden)]
+    #[inline]
+    pub fn ___new(make: impl FnOnce(LendToken<L>) -> F) -> Self {
+        let id = Id::next();
+        Self {
+            fut: Some(PinCell::new(make(LendToken(id, PhantomData)))),
+            id,
+            poisoned: false,
+            _ph: PhantomData,
+        }
+    }
+
+    fn lend_next<'s>(self: Pin<&'s mut Self>) -> Option<L::Item<'s>> {
+        let self_ = unsafe { self.get_unchecked_mut() };
+        if self_.poisoned {
+            return None;
+        }
+        let mut slot = unsafe { Pin::new_unchecked(&mut self_.fut) };
+        let fut = slot.as_mut().as_pin_mut()?.get_pin_mut();
+        let mut state = State {
+            id: self_.id,
+            out: None::<L::Item<'s>>,
+        };
+
+        self_.poisoned = true;
+        let poll = slot::poll_with((&raw mut state).cast_const().cast(), None, |cx| {
+            fut.poll(cx)
+        });
+        self_.poisoned = false;
+        match poll {
+            Poll::Ready(()) => {
+                slot.set(None);
+                None
+            }
+            // item was saved into out. it borrows from the future, that can't be resumed until `'s` ends
+            Poll::Pending => Some(state.out.expect(PENDING_ERROR)),
+        }
+    }
+
+    /// Whether the generator has finished.
+    #[inline]
+    pub fn is_complete(&self) -> bool {
+        self.fut.is_none()
+    }
+
+    /// Whether the generator's body panicked. Poisoned generator can't be resumed anymore, and lends nothing.
+    #[inline]
+    pub fn is_poisoned(&self) -> bool {
+        self.poisoned
+    }
+}
+
+impl<F: Future<Output = ()>, L: Lend> LendingIterator for Pin<&mut LendingGn<F, L>> {
+    type Item<'a>
+        = L::Item<'a>
+    where
+        Self: 'a;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item<'_>> {
+        self.as_mut().lend_next()
+    }
+}
+
+impl<F: Future<Output = ()> + Unpin, L: Lend> LendingIterator for LendingGn<F, L> {
+    type Item<'a>
+        = L::Item<'a>
+    where
+        Self: 'a;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item<'_>> {
+        Pin::new(self).lend_next()
+    }
+}
@@ -1,61 +1,323 @@
-use crate::waker::make;
-use core::{marker::PhantomData, mem::MaybeUninit, pin::Pin, task::Context};
+use crate::{Generator, GeneratorState, Hint, Len, YieldedAfterClose, cell::PinCell, slot::Id};
+use core::{iter::FusedIterator, marker::PhantomData, pin::Pin};
 
 #[macro_export]
 macro_rules! gn_type {
     ($t:ty) => {
-        $crate::sync::Gn<impl ::core::future::Future<Output = ()>, $t>
+        $crate::gn_type!($t, ())
+    };
+    ($t:ty, $r:ty) => {
+        $crate::gn_type!($t, $r, ())
+    };
+    ($t:ty, $r:ty, $a:ty) => {
+        $crate::gn_type!($t, $r, $a, ())
+    };
+    ($t:ty, $r:ty, $a:ty, $h:ty) => {
+        $crate::sync::Gn<impl $crate::sync::Raw<$t, $r, $a>, $t, $r, $a, $h>
     };
 }
 
+mod raw;
+#[cfg(feature = "nightly")]
+#[doc(hidden)]
+pub use raw::Native;
+#[doc(hidden)]
+pub use raw::{Closable, Raw};
+
 mod yld;
 #[doc(hidden)]
-pub use yld::Yield;
+pub use yld::{ClosableYield, SetHint, Yield};
+
+mod delegate;
+#[doc(hidden)]
+pub use delegate::{ViaGn, ViaIter, Wrap};
+
+mod lending;
+#[doc(hidden)]
+pub use lending::{LendToken, LendYield, LendingGn};
+
+/// Generator's future got pending without yielding, so it wasn't generated by `gn!`
+const PENDING_ERROR: &str = "generator's future is pending, but nothing was yielded";
+
+/// Proof that the code belongs to a generator yielding `O` and resumed with `A`.
+///
+/// Only [`Gn::___new`] creates these, so that yields of the generated code need no `unsafe`.
+/// Carries the id of its generator, as it may still be smuggled into another one.
+pub struct Token<O, A>(Id, Invariant<(O, A)>);
+
+/// Neither co- nor contravariant, yet `Send` and `Sync`
+type Invariant<T> = PhantomData<fn(T) -> T>;
+
+#[repr(C)]
+struct State<O, A> {
+    /// Must be the first field, see [`crate::slot`]
+    pub id: Id,
+    pub out: Option<O>,
+    pub arg: Option<A>,
+    /// Generator is being closed, see [`Gn::close`]
+    pub cancelled: bool,
+    /// Set by [`SetHint`], for the generator to update its hint with
+    pub hint: Option<(usize, Option<usize>)>,
+}
 
 #[doc(hidden)]
-pub struct Gn<F: Future<Output = ()>, O> {
-    pub fut: F,
-    pub _ph: PhantomData<O>,
-}
-
-impl<F: Future<Output = ()>, O> Gn<F, O> {
-    fn gn_next(mut self: Pin<&mut Self>) -> Option<O> {
-        let fut;
-        unsafe {
-            let self_ = self.as_mut().get_unchecked_mut();
-            fut = Pin::new_unchecked(&mut self_.fut);
-        }
-        let mut state = MaybeUninit::uninit();
-        let waker = make((&raw mut state).cast_const().cast());
-        match fut.poll(&mut Context::from_waker(&waker)) {
-            core::task::Poll::Ready(()) => {
-                // finished generation
-                None
+pub struct Gn<F, O, R = (), A = (), H = ()> {
+    /// `None` once the generator completes
+    fut: Option<PinCell<F>>,
+    /// Id of the tokens handed to the future
+    id: Id,
+    /// Set while the future is polled, so it stays set if the body panics
+    poisoned: bool,
+    /// Whether the generator was resumed at least once
+    started: bool,
+    hint: H,
+    _ph: PhantomData<(O, R, A)>,
+}
+
+impl<F, O, R, A, H> Gn<F, O, R, A, H> {
+    /// Creates the generator from the future (or coroutine) built by `make`.
+    ///
+    /// Only `gn!` should use this function.
+    #[doc(hidden)]
+    #[inline]
+    pub fn ___new(make: impl FnOnce(Token<O, A>) -> F, hint: H) -> Self {
+        let id = Id::next();
+        Self {
+            fut: Some(PinCell::new(make(Token(id, PhantomData)))),
+            id,
+            poisoned: false,
+            started: false,
+            hint,
+            _ph: PhantomData,
+        }
+    }
+}
+
+impl<F: Raw<O, R, A>, O, R, A, H: Hint> Gn<F, O, R, A, H> {
+    /// Runs the generator until the next `yield`, or until it completes.
+    ///
+    /// `arg` is what the pending `yield` evaluates to (or what generator's argument is bound to, on the first resume).
+    ///
+    /// # Panics
+    ///
+    /// If the generator has already completed (see [`Gn::is_complete`]), or if its body panicked before (see [`Gn::is_poisoned`]).
+    pub fn resume_with(self: Pin<&mut Self>, arg: A) -> GeneratorState<O, R> {
+        let self_ = unsafe { self.get_unchecked_mut() };
+        if self_.poisoned {
+            panic!("generator resumed after its body panicked");
+        }
+        let mut slot = unsafe { Pin::new_unchecked(&mut self_.fut) };
+        let Some(fut) = slot.as_mut().as_pin_mut().map(PinCell::get_pin_mut) else {
+            panic!("generator resumed after completion");
+        };
+        self_.poisoned = true;
+        self_.started = true;
+        let state = fut.resume_raw(self_.id, arg, &mut self_.hint);
+        self_.poisoned = false;
+        match state {
+            GeneratorState::Complete(ret) => {
+                // finished generation, the state machine is of no use anymore
+                slot.set(None);
+                // generator is complete already, if the check panics
+                self_.hint.completed();
+                GeneratorState::Complete(ret)
+            }
+            GeneratorState::Yielded(item) => {
+                self_.hint.yielded();
+                GeneratorState::Yielded(item)
             }
-            core::task::Poll::Pending => {
-                // item was saved into out
-                Some(unsafe { state.assume_init_read() })
+        }
+    }
+
+    /// Finishes the generator early.
+    ///
+    /// `closable` generator suspended at a `yield` is resumed one last time, with the `yield` evaluating to `Err(Cancelled)`,
+    /// so that it can clean up and return. Any other generator is just dropped, as it wouldn't know what happened.
+    /// Returns the completion value, if the generator has returned it now.
+    ///
+    /// # Errors
+    ///
+    /// If the generator yields instead of returning. It's dropped then, as it can't be resumed anymore.
+    pub fn close(self: Pin<&mut Self>) -> Result<Option<R>, YieldedAfterClose<O>> {
+        let self_ = unsafe { self.get_unchecked_mut() };
+        let mut slot = unsafe { Pin::new_unchecked(&mut self_.fut) };
+        let state = match slot.as_mut().as_pin_mut().map(PinCell::get_pin_mut) {
+            // body panicked before, there's nothing to run anymore
+            Some(_) if self_.poisoned => None,
+            // not started generator has no `yield` to return from
+            Some(_) if !self_.started => None,
+            Some(fut) => {
+                self_.poisoned = true;
+                let state = fut.cancel_raw(self_.id, &mut self_.hint);
+                self_.poisoned = false;
+                state
             }
+            None => None,
+        };
+        // generator is done, either way
+        slot.set(None);
+        match state {
+            None => Ok(None),
+            Some(GeneratorState::Complete(ret)) => Ok(Some(ret)),
+            Some(GeneratorState::Yielded(item)) => Err(YieldedAfterClose(item)),
+        }
+    }
+
+    /// Whether the generator has returned its completion value.
+    #[inline]
+    pub fn is_complete(&self) -> bool {
+        self.fut.is_none()
+    }
+
+    /// Whether the generator's body panicked. Poisoned generator can't be resumed anymore, and iterates as empty.
+    #[inline]
+    pub fn is_poisoned(&self) -> bool {
+        self.poisoned
+    }
+
+    /// Bounds on the number of items left, as declared in the generator header.
+    #[inline]
+    pub fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.is_complete() || self.is_poisoned() {
+            (0, Some(0))
+        } else {
+            self.hint.size_hint()
         }
     }
 }
 
-impl<F: Future<Output = ()>, O> Iterator for Pin<&mut Gn<F, O>> {
+// future is the only field that's pinned
+impl<F: Unpin, O, R, A, H> Unpin for Gn<F, O, R, A, H> {}
+
+/// Heap-pinned generator, with its state machine type erased, that stays `Send`.
+#[cfg(feature = "alloc")]
+pub type BoxedGn<'a, O, R = (), A = (), H = ()> =
+    Gn<Pin<alloc::boxed::Box<dyn Raw<O, R, A> + Send + 'a>>, O, R, A, H>;
+
+/// Heap-pinned generator, with its state machine type erased, that's not `Send`.
+#[cfg(feature = "alloc")]
+pub type LocalBoxedGn<'a, O, R = (), A = (), H = ()> =
+    Gn<Pin<alloc::boxed::Box<dyn Raw<O, R, A> + 'a>>, O, R, A, H>;
+
+#[cfg(feature = "alloc")]
+impl<F: Raw<O, R, A>, O, R, A, H> Gn<F, O, R, A, H> {
+    /// Moves generator's state machine to the heap, so that the generator is [`Unpin`] and has a nameable type.
+    pub fn boxed<'a>(self) -> BoxedGn<'a, O, R, A, H>
+    where
+        F: Send + 'a,
+    {
+        self.map_fut(|fut| alloc::boxed::Box::pin(fut) as _)
+    }
+
+    /// Same as [`Gn::boxed`], for the generators that aren't `Send`.
+    pub fn boxed_local<'a>(self) -> LocalBoxedGn<'a, O, R, A, H>
+    where
+        F: 'a,
+    {
+        self.map_fut(|fut| alloc::boxed::Box::pin(fut) as _)
+    }
+
+    fn map_fut<G>(self, f: impl FnOnce(F) -> G) -> Gn<G, O, R, A, H> {
+        Gn {
+            fut: self.fut.map(|fut| PinCell::new(f(fut.into_inner()))),
+            id: self.id,
+            poisoned: self.poisoned,
+            started: self.started,
+            hint: self.hint,
+            _ph: PhantomData,
+        }
+    }
+}
+
+impl<F: Raw<O, R>, O, R, H: Hint> Gn<F, O, R, (), H> {
+    /// Runs the generator until the next `yield`, or until it completes.
+    #[inline]
+    pub fn resume(self: Pin<&mut Self>) -> GeneratorState<O, R> {
+        self.resume_with(())
+    }
+
+    fn gn_next(self: Pin<&mut Self>) -> Option<O> {
+        if self.is_complete() || self.is_poisoned() {
+            return None;
+        }
+        match self.resume() {
+            GeneratorState::Yielded(item) => Some(item),
+            GeneratorState::Complete(_) => None,
+        }
+    }
+}
+
+impl<F: Raw<O, R>, O, R, H: Hint> Iterator for Pin<&mut Gn<F, O, R, (), H>> {
     type Item = O;
 
     #[inline]
     fn next(&mut self) -> Option<Self::Item> {
         self.as_mut().gn_next()
     }
-}
 
-pub trait Generator
-where
-    for<'a> Pin<&'a mut Self>: IntoIterator<Item = Self::Item>,
-{
-    type Item;
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        Gn::size_hint(self)
+    }
 }
 
-impl<F: Future<Output = ()>, O> Generator for Gn<F, O> {
+impl<F: Raw<O, R> + Unpin, O, R, H: Hint> Iterator for Gn<F, O, R, (), H> {
     type Item = O;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        Pin::new(self).gn_next()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        Gn::size_hint(self)
+    }
+}
+
+impl<F: Raw<O, R>, O, R, H: Hint> FusedIterator for Pin<&mut Gn<F, O, R, (), H>> {}
+
+impl<F: Raw<O, R> + Unpin, O, R, H: Hint> FusedIterator for Gn<F, O, R, (), H> {}
+
+// declared length is exact
+impl<F: Raw<O, R>, O, R> ExactSizeIterator for Pin<&mut Gn<F, O, R, (), Len>> {}
+
+impl<F: Raw<O, R> + Unpin, O, R> ExactSizeIterator for Gn<F, O, R, (), Len> {}
+
+impl<F: Raw<O, R>, O, R, H: Hint> Generator for Gn<F, O, R, (), H> {
+    type Yield = O;
+    type Return = R;
+
+    #[inline]
+    fn resume(self: Pin<&mut Self>) -> GeneratorState<O, R> {
+        Gn::resume(self)
+    }
+}
+
+#[cfg(feature = "nightly")]
+impl<F: Raw<O, R, A>, O, R, A, H: Hint> core::ops::Coroutine<A> for Gn<F, O, R, A, H> {
+    type Yield = O;
+    type Return = R;
+
+    #[inline]
+    fn resume(self: Pin<&mut Self>, arg: A) -> core::ops::CoroutineState<O, R> {
+        match self.resume_with(arg) {
+            GeneratorState::Yielded(item) => core::ops::CoroutineState::Yielded(item),
+            GeneratorState::Complete(ret) => core::ops::CoroutineState::Complete(ret),
+        }
+    }
+}
+
+// async blocks are never `Clone`, but some of the native coroutines are
+impl<F: Clone, O, R, A, H: Clone> Clone for Gn<F, O, R, A, H> {
+    fn clone(&self) -> Self {
+        Self {
+            fut: self.fut.clone(),
+            id: self.id,
+            poisoned: self.poisoned,
+            started: self.started,
+            hint: self.hint.clone(),
+            _ph: PhantomData,
+        }
+    }
 }
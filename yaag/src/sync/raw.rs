@@ -0,0 +1,170 @@
+use core::pin::Pin;
+
+use crate::{GeneratorState, Hint, slot::Id};
+
+/// Generator's state machine, that is resumed with `A`, yields `O` and completes with `R`.
+///
+/// It's an `async` block (with [`Yield`](super::Yield) talking to [`Raw::resume_raw`] through the fake waker or the thread-local),
+/// or a native coroutine with `nightly` feature.
+pub trait Raw<O, R, A = ()> {
+    /// `id` is the one of the tokens handed to the generator code, and `hint` is updated with its `size_hint!(..)`s.
+    fn resume_raw(
+        self: Pin<&mut Self>,
+        id: Id,
+        arg: A,
+        hint: &mut dyn Hint,
+    ) -> GeneratorState<O, R>;
+
+    /// Resumes the generator with its pending `yield` evaluating to [`Cancelled`](crate::Cancelled).
+    ///
+    /// `None` if the generator can't be told so, and is to be just dropped.
+    #[inline]
+    fn cancel_raw(
+        self: Pin<&mut Self>,
+        _id: Id,
+        _hint: &mut dyn Hint,
+    ) -> Option<GeneratorState<O, R>> {
+        None
+    }
+}
+
+/// Polls the generator's future once, with the pending `yield` evaluating to `arg` (or to `Cancelled`, if there's none).
+fn poll_raw<F: Future<Output = R>, O, R, A>(
+    fut: Pin<&mut F>,
+    id: Id,
+    arg: Option<A>,
+    hint: &mut dyn Hint,
+) -> GeneratorState<O, R> {
+    use super::{PENDING_ERROR, State};
+    use crate::slot;
+    use core::task::Poll;
+
+    let mut state = State {
+        id,
+        out: None,
+        cancelled: arg.is_none(),
+        arg,
+        hint: None,
+    };
+    let poll = slot::poll_with((&raw mut state).cast_const().cast(), None, |cx| {
+        fut.poll(cx)
+    });
+    // no more references to `state` exist at this point
+    if let Some((lo, hi)) = state.hint {
+        hint.update(lo, hi);
+    }
+    match poll {
+        Poll::Ready(ret) => GeneratorState::Complete(ret),
+        // item was saved into out
+        Poll::Pending => GeneratorState::Yielded(state.out.expect(PENDING_ERROR)),
+    }
+}
+
+impl<F: Future<Output = R>, O, R, A> Raw<O, R, A> for F {
+    #[inline]
+    fn resume_raw(
+        self: Pin<&mut Self>,
+        id: Id,
+        arg: A,
+        hint: &mut dyn Hint,
+    ) -> GeneratorState<O, R> {
+        poll_raw(self, id, Some(arg), hint)
+    }
+}
+
+/// Native coroutine of a synchronous generator, with `nightly` feature.
+#[cfg(feature = "nightly")]
+#[derive(Clone)]
+pub struct Native<C>(C);
+
+#[cfg(feature = "nightly")]
+impl<C> Native<C> {
+    #[doc(hidden)]
+    #[inline]
+    pub fn ___new(coroutine: C) -> Self {
+        Self(coroutine)
+    }
+}
+
+#[cfg(feature = "nightly")]
+impl<C: core::ops::Coroutine<A, Yield = O, Return = R>, O, R, A> Raw<O, R, A> for Native<C> {
+    #[inline]
+    fn resume_raw(
+        self: Pin<&mut Self>,
+        _id: Id,
+        arg: A,
+        _hint: &mut dyn Hint,
+    ) -> GeneratorState<O, R> {
+        // coroutine is pinned structurally, and native `yield` needs no tokens
+        match unsafe { self.map_unchecked_mut(|s| &mut s.0) }.resume(arg) {
+            core::ops::CoroutineState::Yielded(item) => GeneratorState::Yielded(item),
+            core::ops::CoroutineState::Complete(ret) => GeneratorState::Complete(ret),
+        }
+    }
+}
+
+// type-erased state machines of the boxed generators
+#[cfg(feature = "alloc")]
+macro_rules! boxed_raw {
+    ($($bounds:tt)*) => {
+        impl<O, R, A> Raw<O, R, A> for Pin<alloc::boxed::Box<dyn Raw<O, R, A> $($bounds)* + '_>> {
+            #[inline]
+            fn resume_raw(self: Pin<&mut Self>, id: Id, arg: A, hint: &mut dyn Hint) -> GeneratorState<O, R> {
+                self.get_mut().as_mut().resume_raw(id, arg, hint)
+            }
+
+            #[inline]
+            fn cancel_raw(self: Pin<&mut Self>, id: Id, hint: &mut dyn Hint) -> Option<GeneratorState<O, R>> {
+                self.get_mut().as_mut().cancel_raw(id, hint)
+            }
+        }
+    };
+}
+
+#[cfg(feature = "alloc")]
+boxed_raw!();
+#[cfg(feature = "alloc")]
+boxed_raw!(+ Send);
+
+/// Future of a `closable` generator, that can be told it's closed. It's an `async` block with either backend.
+pub struct Closable<F>(F);
+
+impl<F> Closable<F> {
+    #[doc(hidden)]
+    #[inline]
+    pub fn ___new(fut: F) -> Self {
+        Self(fut)
+    }
+}
+
+impl<F: Future<Output = R>, O, R, A> Raw<O, R, A> for Closable<F> {
+    #[inline]
+    fn resume_raw(
+        self: Pin<&mut Self>,
+        id: Id,
+        arg: A,
+        hint: &mut dyn Hint,
+    ) -> GeneratorState<O, R> {
+        // future is pinned structurally
+        poll_raw(
+            unsafe { self.map_unchecked_mut(|s| &mut s.0) },
+            id,
+            Some(arg),
+            hint,
+        )
+    }
+
+    #[inline]
+    fn cancel_raw(
+        self: Pin<&mut Self>,
+        id: Id,
+        hint: &mut dyn Hint,
+    ) -> Option<GeneratorState<O, R>> {
+        Some(poll_raw(
+            unsafe { self.map_unchecked_mut(|s| &mut s.0) },
+            id,
+            None::<A>,
+            hint,
+        ))
+    }
+}
@@ -1,29 +1,93 @@
 use core::{
-    mem::MaybeUninit,
+    marker::PhantomData,
     pin::Pin,
     task::{Context, Poll},
 };
 
-pub struct Yield<O>(Option<O>);
+use crate::{
+    Cancelled,
+    slot::{self, Id},
+    sync::{State, Token},
+};
+
+pub struct Yield<O, A>(Id, Option<O>, PhantomData<A>);
 
-impl<O> Yield<O> {
-    /// SAFETY: **never** use this function.
+impl<O, A> Yield<O, A> {
     #[doc(hidden)]
-    pub unsafe fn ___make(o: O) -> Self {
-        Self(Some(o))
+    #[inline]
+    pub fn ___make(token: &Token<O, A>, o: O) -> Self {
+        Self(token.0, Some(o), PhantomData)
+    }
+
+    #[doc(hidden)]
+    #[inline]
+    pub fn ___first(token: &Token<O, A>) -> Self {
+        Self(token.0, None, PhantomData)
     }
 }
 
-impl<O> Future for Yield<O> {
-    type Output = ();
+impl<O, A> Future for Yield<O, A> {
+    type Output = A;
 
     fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> core::task::Poll<Self::Output> {
-        if let Some(data) = unsafe { self.get_unchecked_mut().0.take() } {
-            let out = unsafe { &mut *cx.waker().data().cast::<MaybeUninit<O>>().cast_mut() };
-            out.write(data);
+        let state = unsafe { &mut *slot::data(cx, self.0).cast::<State<O, A>>().cast_mut() };
+        if let Some(data) = unsafe { self.get_unchecked_mut().1.take() } {
+            state.out = Some(data);
             Poll::Pending
         } else {
-            Poll::Ready(())
+            // generator was resumed, so there must be an argument
+            Poll::Ready(
+                state
+                    .arg
+                    .take()
+                    .expect("generator resumed without an argument"),
+            )
+        }
+    }
+}
+
+/// `yield` of a `closable` generator, evaluating to [`Cancelled`] once the generator is closed
+pub struct ClosableYield<O, A>(Yield<O, A>);
+
+impl<O, A> ClosableYield<O, A> {
+    #[doc(hidden)]
+    #[inline]
+    pub fn ___make(token: &Token<O, A>, o: O) -> Self {
+        Self(Yield::___make(token, o))
+    }
+}
+
+impl<O, A> Future for ClosableYield<O, A> {
+    type Output = Result<A, Cancelled>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> core::task::Poll<Self::Output> {
+        let state = unsafe { &*slot::data(cx, self.0.0).cast::<State<O, A>>() };
+        if state.cancelled && self.0.1.is_none() {
+            return Poll::Ready(Err(Cancelled));
         }
+        // yield itself is never pinned
+        let yld = unsafe { self.map_unchecked_mut(|s| &mut s.0) };
+        yld.poll(cx).map(Ok)
+    }
+}
+
+/// `size_hint!(..)`, handing the re-declared hint to the generator
+pub struct SetHint<O, A>(Id, (usize, Option<usize>), PhantomData<(O, A)>);
+
+impl<O, A> SetHint<O, A> {
+    #[doc(hidden)]
+    #[inline]
+    pub fn ___make(token: &Token<O, A>, hint: (usize, Option<usize>)) -> Self {
+        Self(token.0, hint, PhantomData)
+    }
+}
+
+impl<O, A> Future for SetHint<O, A> {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> core::task::Poll<Self::Output> {
+        let state = unsafe { &mut *slot::data(cx, self.0).cast::<State<O, A>>().cast_mut() };
+        state.hint = Some(self.1);
+        Poll::Ready(())
     }
 }
@@ -1,8 +1,11 @@
 use core::task::{RawWaker, RawWakerVTable, Waker};
 
-const VTABLE: RawWakerVTable = RawWakerVTable::new(clone, wake, wake_by_ref, drop);
-unsafe fn clone(data: *const ()) -> RawWaker {
-    RawWaker::new(data, &VTABLE)
+// `static`, so that there's exactly one address to compare against
+static VTABLE: RawWakerVTable = RawWakerVTable::new(clone, wake, wake_by_ref, drop);
+/// Clones may outlive the poll, so these don't carry the pointer, and fail the check in [`data`]
+static DEAD_VTABLE: RawWakerVTable = RawWakerVTable::new(clone, wake, wake_by_ref, drop);
+unsafe fn clone(_: *const ()) -> RawWaker {
+    RawWaker::new(core::ptr::null(), &DEAD_VTABLE)
 }
 unsafe fn wake(_: *const ()) {}
 unsafe fn wake_by_ref(_: *const ()) {}
@@ -11,3 +14,17 @@ unsafe fn drop(_: *const ()) {}
 pub fn make(data: *const ()) -> Waker {
     unsafe { Waker::new(data, &VTABLE) }
 }
+
+/// Retrieves the data pointer from the waker created by [`make`].
+///
+/// Panics if the waker came from somewhere else.
+/// That would mean generator's internal future got polled by a foreign executor, and using the pointer would be UB.
+/// Constructors of `Yield` and `Await` are safe, so the check can't be left to debug builds.
+#[inline]
+pub fn data(waker: &Waker) -> *const () {
+    assert!(
+        core::ptr::eq(waker.vtable(), &VTABLE),
+        "yaag's internal future was polled with a foreign waker; `Yield` and `Await` must only be awaited inside of the generator code"
+    );
+    waker.data()
+}
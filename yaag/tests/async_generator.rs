@@ -0,0 +1,115 @@
+#![cfg(feature = "async")]
+#![cfg_attr(feature = "async-iter", feature(async_iterator))]
+
+use core::{
+    pin::{Pin, pin},
+    task::{Context, Poll, Waker},
+};
+use yaag::{AsyncGenerator, gn};
+
+struct Countdown(u32);
+
+impl AsyncGenerator for Countdown {
+    type Yield = u32;
+
+    fn poll_generate(self: Pin<&mut Self>, _: &mut Context<'_>) -> Option<Poll<u32>> {
+        let this = self.get_mut();
+        let item = this.0.checked_sub(1)?;
+        this.0 = item;
+        Some(Poll::Ready(item))
+    }
+}
+
+fn drain<G: AsyncGenerator>(gn: G) -> Vec<G::Yield> {
+    let mut gn = pin!(gn);
+    let mut cx = Context::from_waker(Waker::noop());
+    let mut items = Vec::new();
+    while let Some(poll) = gn.as_mut().poll_generate(&mut cx) {
+        if let Poll::Ready(item) = poll {
+            items.push(item);
+        }
+    }
+    items
+}
+
+#[test]
+fn hand_written() {
+    assert_eq!(drain(Countdown(3)), vec![2, 1, 0]);
+}
+
+#[test]
+fn generated() {
+    let gn = gn!(async gen {
+        yield 1;
+        async {}.await;
+        yield 2;
+    } -> i32);
+    assert_eq!(drain(gn), vec![1, 2]);
+}
+
+#[test]
+fn pending_is_not_the_end() {
+    let gn = gn!(async gen {
+        let mut ready = false;
+        core::future::poll_fn(|cx| {
+            if ready {
+                Poll::Ready(())
+            } else {
+                ready = true;
+                cx.waker().wake_by_ref();
+                Poll::Pending
+            }
+        })
+        .await;
+        yield 'a';
+    } -> char);
+    let mut gn = pin!(gn);
+    let mut cx = Context::from_waker(Waker::noop());
+    assert_eq!(gn.as_mut().poll_generate(&mut cx), Some(Poll::Pending));
+    assert_eq!(gn.as_mut().poll_generate(&mut cx), Some(Poll::Ready('a')));
+    assert_eq!(gn.as_mut().poll_generate(&mut cx), None);
+    assert_eq!(gn.as_mut().poll_generate(&mut cx), None);
+}
+
+#[cfg(feature = "async-iter")]
+#[test]
+fn async_iterator() {
+    use core::async_iter::AsyncIterator;
+
+    let gn = gn!(async gen {
+        yield 1;
+        yield 2;
+    } -> i32, len = 2);
+    let mut gn = pin!(gn);
+    let mut cx = Context::from_waker(Waker::noop());
+    assert_eq!(gn.size_hint(), (2, Some(2)));
+    assert_eq!(gn.as_mut().poll_next(&mut cx), Poll::Ready(Some(1)));
+    assert_eq!(gn.as_mut().poll_next(&mut cx), Poll::Ready(Some(2)));
+    assert_eq!(gn.as_mut().poll_next(&mut cx), Poll::Ready(None));
+}
+
+#[test]
+fn delegates_without_streams() {
+    let inner = gn!(async gen {
+        yield 10;
+        yield 20;
+    } -> u32);
+    let gn = gn!(async move gen {
+        yield from Countdown(2);
+        yield from inner;
+        yield from [30, 40];
+    } -> u32);
+    assert_eq!(drain(gn), vec![1, 0, 10, 20, 30, 40]);
+}
+
+#[test]
+fn inherent_poll_next() {
+    let gn = gn!(async gen {
+        yield 1;
+    } -> i32);
+    let mut gn = pin!(gn);
+    let mut cx = Context::from_waker(Waker::noop());
+    assert_eq!(gn.as_mut().poll_next(&mut cx), Poll::Ready(Some(1)));
+    assert_eq!(gn.as_mut().poll_next(&mut cx), Poll::Ready(None));
+    assert!(gn.is_complete());
+}
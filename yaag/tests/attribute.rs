@@ -0,0 +1,75 @@
+#![cfg_attr(feature = "nightly", feature(coroutines))]
+
+use core::pin::pin;
+use yaag::generator;
+
+#[generator(yield = T)]
+fn repeat<T: Clone>(t: T, n: usize) {
+    for _ in 0..n {
+        r#yield!(t.clone());
+    }
+}
+
+#[test]
+fn repeat_works() {
+    let gn = repeat("a", 3);
+    let gn = pin!(gn);
+    assert_eq!(gn.collect::<Vec<_>>(), vec!["a", "a", "a"]);
+}
+
+#[generator(yield = &'a T)]
+fn every_other<'a, T>(items: &'a [T]) {
+    let mut iter = items.iter();
+    while let Some(item) = iter.next() {
+        r#yield!(item);
+        iter.next();
+    }
+}
+
+#[test]
+fn borrows_arguments() {
+    let items = [1, 2, 3, 4, 5];
+    let gn = every_other(&items);
+    let gn = pin!(gn);
+    assert_eq!(gn.collect::<Vec<_>>(), vec![&1, &3, &5]);
+}
+
+struct Counter {
+    end: u32,
+}
+
+impl Counter {
+    #[generator(yield = u32)]
+    fn count(&self) {
+        for i in 0..self.end {
+            r#yield!(i);
+        }
+    }
+}
+
+#[test]
+fn method_works() {
+    let counter = Counter { end: 4 };
+    let gn = counter.count();
+    let gn = pin!(gn);
+    assert_eq!(gn.collect::<Vec<_>>(), vec![0, 1, 2, 3]);
+}
+
+#[cfg(feature = "futures")]
+#[generator(yield = u64)]
+async fn ticks(n: u64) {
+    use std::time::Duration;
+
+    for i in 0..n {
+        tokio::time::sleep(Duration::from_millis(10 * i)).await;
+        r#yield!(i);
+    }
+}
+
+#[cfg(feature = "futures")]
+#[tokio::test]
+async fn async_fn_works() {
+    use futures_util::StreamExt;
+
+    assert_eq!(ticks(5).collect::<Vec<_>>().await, vec![0, 1, 2, 3, 4]);
+}
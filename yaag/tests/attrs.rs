@@ -0,0 +1,67 @@
+#![cfg_attr(feature = "nightly", feature(coroutines))]
+
+use core::pin::pin;
+use yaag::gn;
+
+#[test]
+fn cfg_on_statements() {
+    let gn = gn!(gen {
+        #[cfg(test)]
+        yield 1;
+        #[cfg(not(test))]
+        yield 2;
+        #[cfg(not(test))]
+        let value = 3;
+        #[cfg(test)]
+        let value = 4;
+        yield value;
+    } -> i32);
+    let gn = pin!(gn);
+    assert_eq!(gn.collect::<Vec<_>>(), vec![1, 4]);
+}
+
+struct Point {
+    x: i32,
+    #[cfg(test)]
+    y: i32,
+}
+
+#[test]
+fn cfg_on_arms_and_fields() {
+    let gn = gn!(gen {
+        for i in 0..3 {
+            let point = Point {
+                x: i,
+                #[cfg(test)]
+                y: match i {
+                    #[cfg(not(test))]
+                    0 => 100,
+                    0 => 10,
+                    #[cfg(test)]
+                    1 => 20,
+                    _ => 30,
+                },
+            };
+            yield point.x + point.y;
+        }
+    } -> i32);
+    let gn = pin!(gn);
+    assert_eq!(gn.collect::<Vec<_>>(), vec![10, 21, 32]);
+}
+
+#[test]
+fn lint_attributes() {
+    let gn = gn!(gen {
+        #[allow(unused_variables)]
+        let unused = 1;
+        #[expect(unused_mut)]
+        let mut value = 2;
+        #[rustfmt::skip]
+        let other   =   3;
+        #[cfg_attr(test, allow(unused_variables))]
+        let also_unused = 4;
+        yield value + other;
+    } -> i32);
+    let gn = pin!(gn);
+    assert_eq!(gn.collect::<Vec<_>>(), vec![5]);
+}
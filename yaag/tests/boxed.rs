@@ -0,0 +1,173 @@
+#![cfg(feature = "alloc")]
+#![cfg_attr(feature = "nightly", feature(coroutines))]
+
+use yaag::{BoxedGn, GeneratorState, LocalBoxedGn, gn};
+
+fn countdown(from: u32) -> BoxedGn<'static, u32> {
+    gn!(move gen {
+        for i in (1..=from).rev() {
+            yield i;
+        }
+    } -> u32)
+    .boxed()
+}
+
+#[test]
+fn iterates_without_pinning() {
+    assert_eq!(countdown(3).collect::<Vec<_>>(), vec![3, 2, 1]);
+}
+
+#[test]
+fn returns_started_generator() {
+    let mut gn = countdown(5);
+    assert_eq!(gn.next(), Some(5));
+    assert_eq!(gn.next(), Some(4));
+    assert_eq!(gn.sum::<u32>(), 6);
+}
+
+struct Counter<'a> {
+    items: BoxedGn<'a, &'a str>,
+    count: usize,
+}
+
+impl<'a> Iterator for Counter<'a> {
+    type Item = (usize, &'a str);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = self.items.next()?;
+        self.count += 1;
+        Some((self.count, item))
+    }
+}
+
+#[test]
+fn stored_in_struct() {
+    let text = String::from("a b c");
+    let counter = Counter {
+        items: gn!(gen {
+            for word in text.split(' ') {
+                yield word;
+            }
+        } -> &str)
+        .boxed(),
+        count: 0,
+    };
+    assert_eq!(
+        counter.collect::<Vec<_>>(),
+        vec![(1, "a"), (2, "b"), (3, "c")]
+    );
+}
+
+#[test]
+fn trait_object() {
+    let gns: Vec<Box<dyn Iterator<Item = u32>>> =
+        vec![Box::new(countdown(2)), Box::new(countdown(1))];
+    assert_eq!(gns.into_iter().flatten().collect::<Vec<_>>(), vec![2, 1, 1]);
+}
+
+#[test]
+fn keeps_completion_value() {
+    let mut gn: BoxedGn<'_, u8, &str> = gn!(gen {
+        yield 1;
+        "done"
+    } -> u8, &str)
+    .boxed();
+    assert_eq!(
+        core::pin::Pin::new(&mut gn).resume(),
+        GeneratorState::Yielded(1)
+    );
+    assert_eq!(
+        core::pin::Pin::new(&mut gn).resume(),
+        GeneratorState::Complete("done")
+    );
+}
+
+enum Tree {
+    Leaf(i32),
+    Node(Vec<Tree>),
+}
+
+fn leaves(tree: &Tree) -> BoxedGn<'_, i32> {
+    gn!(move gen {
+        match tree {
+            Tree::Leaf(value) => yield *value,
+            Tree::Node(children) => {
+                for child in children {
+                    yield from leaves(child);
+                }
+            }
+        }
+    } -> i32)
+    .boxed()
+}
+
+#[test]
+fn recursive() {
+    let tree = Tree::Node(vec![
+        Tree::Leaf(1),
+        Tree::Node(vec![Tree::Leaf(2), Tree::Node(vec![Tree::Leaf(3)])]),
+        Tree::Leaf(4),
+    ]);
+    assert_eq!(leaves(&tree).collect::<Vec<_>>(), vec![1, 2, 3, 4]);
+}
+
+#[test]
+fn sent_to_another_thread() {
+    let gn = countdown(3);
+    let items = std::thread::spawn(move || gn.collect::<Vec<_>>());
+    assert_eq!(items.join().unwrap(), vec![3, 2, 1]);
+}
+
+#[test]
+fn local() {
+    use std::rc::Rc;
+
+    let shared = Rc::new(2);
+    let gn: LocalBoxedGn<'_, u32> = gn!(move gen {
+        yield *shared;
+        yield *shared + 1;
+    } -> u32)
+    .boxed_local();
+    assert_eq!(gn.collect::<Vec<_>>(), vec![2, 3]);
+}
+
+#[cfg(feature = "futures")]
+#[tokio::test]
+async fn async_boxed() {
+    use futures_util::StreamExt;
+    use yaag::BoxedAsyncGn;
+
+    fn ticks(n: u32) -> BoxedAsyncGn<'static, u32> {
+        gn!(async move gen {
+            for i in 0..n {
+                tokio::task::yield_now().await;
+                yield i;
+            }
+        } -> u32)
+        .boxed()
+    }
+
+    let mut gn = ticks(3);
+    assert_eq!(gn.next().await, Some(0));
+    assert_eq!(gn.collect::<Vec<_>>().await, vec![1, 2]);
+
+    // stays `Send`, so it may be driven by another task
+    let items = tokio::spawn(ticks(2).collect::<Vec<_>>());
+    assert_eq!(items.await.unwrap(), vec![0, 1]);
+}
+
+#[cfg(feature = "futures")]
+#[tokio::test]
+async fn async_local() {
+    use futures_util::StreamExt;
+    use std::rc::Rc;
+    use yaag::LocalBoxedAsyncGn;
+
+    let shared = Rc::new(1);
+    let gn: LocalBoxedAsyncGn<'_, u32> = gn!(async move gen {
+        tokio::task::yield_now().await;
+        yield *shared;
+    } -> u32)
+    .boxed_local();
+    assert_eq!(gn.collect::<Vec<_>>().await, vec![1]);
+}
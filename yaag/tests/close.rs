@@ -0,0 +1,244 @@
+#![cfg_attr(feature = "nightly", feature(coroutines))]
+
+use core::{cell::Cell, pin::pin};
+use yaag::{Cancelled, GeneratorState, YieldedAfterClose, generator, gn, gn_type};
+
+/// Counts up, recording whether it was closed
+fn counter(closed: &Cell<bool>) -> gn_type!(u32, u32) {
+    gn!(closable move gen {
+        let mut count = 0;
+        loop {
+            if let Err(Cancelled) = (yield count) {
+                closed.set(true);
+                return count;
+            }
+            count += 1;
+        }
+    } -> u32, u32)
+}
+
+#[test]
+fn close_resumes_with_cancelled() {
+    let closed = Cell::new(false);
+    let mut gn = pin!(counter(&closed));
+    assert_eq!(gn.as_mut().resume(), GeneratorState::Yielded(0));
+    assert_eq!(gn.as_mut().resume(), GeneratorState::Yielded(1));
+    assert_eq!(gn.as_mut().close(), Ok(Some(1)));
+    assert!(closed.get());
+    assert!(gn.is_complete());
+    // closing again is a no-op
+    assert_eq!(gn.as_mut().close(), Ok(None));
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn boxed_stays_closable() {
+    let closed = Cell::new(false);
+    let mut gn = counter(&closed).boxed_local();
+    assert_eq!(gn.next(), Some(0));
+    assert_eq!(core::pin::Pin::new(&mut gn).close(), Ok(Some(0)));
+    assert!(closed.get());
+}
+
+#[test]
+fn close_before_start() {
+    let closed = Cell::new(false);
+    let mut gn = pin!(counter(&closed));
+    assert_eq!(gn.as_mut().close(), Ok(None));
+    // there was no `yield` to return `Cancelled` from
+    assert!(!closed.get());
+    assert!(gn.is_complete());
+}
+
+#[test]
+fn close_after_completion() {
+    let mut gn = pin!(gn!(closable gen {
+        let _ = yield 1;
+        2
+    } -> i32, i32));
+    assert_eq!(gn.as_mut().resume(), GeneratorState::Yielded(1));
+    assert_eq!(gn.as_mut().resume(), GeneratorState::Complete(2));
+    assert_eq!(gn.as_mut().close(), Ok(None));
+}
+
+#[test]
+fn close_drops_non_closable() {
+    let dropped = Cell::new(false);
+    let guard = Guard(&dropped);
+    let mut gn = pin!(gn!(move gen {
+        let _guard = guard;
+        yield 1;
+        yield 2;
+    } -> i32));
+    assert_eq!(gn.as_mut().resume(), GeneratorState::Yielded(1));
+    assert_eq!(gn.as_mut().close(), Ok(None));
+    assert!(dropped.get());
+    assert!(gn.is_complete());
+}
+
+#[test]
+fn yielded_after_close() {
+    let mut gn = pin!(gn!(closable gen {
+        let _ = yield 1;
+        let _ = yield 2;
+    } -> i32));
+    assert_eq!(gn.as_mut().resume(), GeneratorState::Yielded(1));
+    assert_eq!(gn.as_mut().close(), Err(YieldedAfterClose(2)));
+    // generator can't be resumed anymore
+    assert!(gn.is_complete());
+}
+
+#[test]
+fn cancelled_with_resume_argument() {
+    let mut gn = pin!(gn!(closable gen(first: i32) {
+        let mut sum = first;
+        while let Ok(arg) = (yield sum) {
+            sum += arg;
+        }
+        sum * 10
+    } -> i32, i32));
+    assert_eq!(gn.as_mut().resume_with(1), GeneratorState::Yielded(1));
+    assert_eq!(gn.as_mut().resume_with(2), GeneratorState::Yielded(3));
+    assert_eq!(gn.as_mut().close(), Ok(Some(30)));
+}
+
+#[test]
+fn delegate_is_cancelled() {
+    let mut gn = pin!(gn!(closable gen {
+        match yield from 0..10 {
+            Ok(()) => "done",
+            Err(Cancelled) => "cancelled",
+        }
+    } -> i32, &'static str));
+    assert_eq!(gn.as_mut().resume(), GeneratorState::Yielded(0));
+    assert_eq!(gn.as_mut().close(), Ok(Some("cancelled")));
+}
+
+#[generator(yield = u32, closable)]
+fn lines(closed: &Cell<bool>) {
+    for line in 0.. {
+        let Ok(()) = r#yield!(line) else {
+            closed.set(true);
+            return;
+        };
+    }
+}
+
+#[test]
+fn attribute() {
+    let closed = Cell::new(false);
+    let mut gn = pin!(lines(&closed));
+    assert_eq!(gn.as_mut().next(), Some(0));
+    assert_eq!(gn.as_mut().next(), Some(1));
+    assert_eq!(gn.as_mut().close(), Ok(Some(())));
+    assert!(closed.get());
+}
+
+struct Guard<'a>(&'a Cell<bool>);
+
+impl Drop for Guard<'_> {
+    fn drop(&mut self) {
+        self.0.set(true);
+    }
+}
+
+#[cfg(feature = "async")]
+mod not_sync {
+    use core::{
+        cell::Cell,
+        future::poll_fn,
+        pin::pin,
+        task::{Context, Poll, Waker},
+    };
+    use yaag::{AsyncGenerator, Cancelled, YieldedAfterClose, async_gn_type, generator, gn};
+
+    /// Future that's pending once
+    async fn tick() {
+        let mut ready = false;
+        poll_fn(|cx| {
+            if ready {
+                Poll::Ready(())
+            } else {
+                ready = true;
+                cx.waker().wake_by_ref();
+                Poll::Pending
+            }
+        })
+        .await;
+    }
+
+    fn sessions(flushed: &Cell<u32>) -> async_gn_type!(u32, u32) {
+        gn!(closable async move gen {
+            let mut count = 0;
+            loop {
+                if let Err(Cancelled) = (yield count) {
+                    // cleanup may await
+                    tick().await;
+                    flushed.set(count);
+                    return count;
+                }
+                count += 1;
+            }
+        } -> u32, u32)
+    }
+
+    #[test]
+    fn close_awaits_cleanup() {
+        let flushed = Cell::new(0);
+        let mut gn = pin!(sessions(&flushed));
+        let mut cx = Context::from_waker(Waker::noop());
+        assert_eq!(gn.as_mut().poll_generate(&mut cx), Some(Poll::Ready(0)));
+        assert_eq!(gn.as_mut().poll_generate(&mut cx), Some(Poll::Ready(1)));
+        let mut close = pin!(gn.as_mut().close());
+        assert_eq!(close.as_mut().poll(&mut cx), Poll::Pending);
+        assert_eq!(close.as_mut().poll(&mut cx), Poll::Ready(Ok(Some(1))));
+        assert_eq!(flushed.get(), 1);
+        assert!(gn.is_complete());
+    }
+
+    #[test]
+    fn close_before_start() {
+        let flushed = Cell::new(5);
+        let mut gn = pin!(sessions(&flushed));
+        let mut cx = Context::from_waker(Waker::noop());
+        assert_eq!(
+            pin!(gn.as_mut().close()).poll(&mut cx),
+            Poll::Ready(Ok(None))
+        );
+        assert_eq!(flushed.get(), 5);
+        assert!(gn.is_complete());
+    }
+
+    #[test]
+    fn yielded_after_close() {
+        let mut gn = pin!(gn!(closable async gen {
+            let _ = yield 1;
+            let _ = yield 2;
+        } -> i32));
+        let mut cx = Context::from_waker(Waker::noop());
+        assert_eq!(gn.as_mut().poll_generate(&mut cx), Some(Poll::Ready(1)));
+        assert_eq!(
+            pin!(gn.as_mut().close()).poll(&mut cx),
+            Poll::Ready(Err(YieldedAfterClose(2)))
+        );
+        assert!(gn.is_complete());
+    }
+
+    #[generator(yield = u32, closable)]
+    async fn ticks() -> bool {
+        for i in 0.. {
+            tick().await;
+            if r#yield!(i) == Err(Cancelled) {
+                return true;
+            }
+        }
+        false
+    }
+
+    #[tokio::test]
+    async fn attribute() {
+        let mut gn = pin!(ticks());
+        assert_eq!(poll_fn(|cx| gn.as_mut().poll_next(cx)).await, Some(0));
+        assert_eq!(gn.as_mut().close().await, Ok(Some(true)));
+    }
+}
@@ -0,0 +1,168 @@
+#![cfg_attr(feature = "nightly", feature(coroutines))]
+
+use core::pin::pin;
+use yaag::{GeneratorState, generator, gn, gn_type};
+
+fn evens(end: u32) -> gn_type!(u32, usize) {
+    gn!(move gen {
+        let mut count = 0;
+        for i in (0..end).filter(|i| i % 2 == 0) {
+            yield i;
+            count += 1;
+        }
+        count
+    } -> u32, usize)
+}
+
+#[test]
+fn resume_reports_completion() {
+    let gn = evens(5);
+    let mut gn = pin!(gn);
+    assert_eq!(gn.as_mut().resume(), GeneratorState::Yielded(0));
+    assert_eq!(gn.as_mut().resume(), GeneratorState::Yielded(2));
+    assert_eq!(gn.as_mut().resume(), GeneratorState::Yielded(4));
+    assert_eq!(gn.as_mut().resume(), GeneratorState::Complete(3));
+}
+
+#[test]
+fn iterator_ignores_completion() {
+    let gn = evens(7);
+    let gn = pin!(gn);
+    assert_eq!(gn.collect::<Vec<_>>(), vec![0, 2, 4, 6]);
+}
+
+#[test]
+fn early_return() {
+    let gn = gn!(gen {
+        for i in 1.. {
+            if i > 3 {
+                return Err(i);
+            }
+            yield i;
+        }
+        Ok(())
+    } -> i32, Result<(), i32>);
+    let mut gn = pin!(gn);
+    assert_eq!(gn.as_mut().resume(), GeneratorState::Yielded(1));
+    assert_eq!(gn.as_mut().resume(), GeneratorState::Yielded(2));
+    assert_eq!(gn.as_mut().resume(), GeneratorState::Yielded(3));
+    assert_eq!(gn.as_mut().resume(), GeneratorState::Complete(Err(4)));
+}
+
+#[test]
+fn no_completion_value() {
+    let gn = gn!(gen { yield 1; } -> i32);
+    let mut gn = pin!(gn);
+    assert_eq!(gn.as_mut().resume(), GeneratorState::Yielded(1));
+    assert_eq!(gn.as_mut().resume(), GeneratorState::Complete(()));
+}
+
+#[generator(yield = char)]
+fn non_ascii(s: &str) -> usize {
+    let mut total = 0;
+    for c in s.chars().filter(|c| !c.is_ascii()) {
+        r#yield!(c);
+        total += c.len_utf8();
+    }
+    total
+}
+
+#[test]
+fn attribute_completion() {
+    let gn = non_ascii("añbç");
+    let mut gn = pin!(gn);
+    assert_eq!(gn.as_mut().resume(), GeneratorState::Yielded('ñ'));
+    assert_eq!(gn.as_mut().resume(), GeneratorState::Yielded('ç'));
+    assert_eq!(gn.as_mut().resume(), GeneratorState::Complete(4));
+}
+
+#[cfg(feature = "async")]
+mod not_sync {
+    use core::{
+        pin::pin,
+        task::{Context, Poll, Waker},
+    };
+    use yaag::{GeneratorState, async_gn_type, generator, gn};
+
+    /// Records of the pages, completing with the cursor of the next page
+    fn page(records: u32, cursor: u32) -> async_gn_type!(u32, Option<u32>) {
+        gn!(async move gen {
+            for record in 0..records {
+                core::future::ready(()).await;
+                yield cursor * 10 + record;
+            }
+            (cursor < 2).then_some(cursor + 1)
+        } -> u32, Option<u32>)
+    }
+
+    #[test]
+    fn poll_resume_reports_completion() {
+        let mut gn = pin!(page(2, 0));
+        let mut cx = Context::from_waker(Waker::noop());
+        assert_eq!(
+            gn.as_mut().poll_resume(&mut cx),
+            Poll::Ready(GeneratorState::Yielded(0))
+        );
+        assert_eq!(
+            gn.as_mut().poll_resume(&mut cx),
+            Poll::Ready(GeneratorState::Yielded(1))
+        );
+        assert_eq!(
+            gn.as_mut().poll_resume(&mut cx),
+            Poll::Ready(GeneratorState::Complete(Some(1)))
+        );
+        assert!(gn.is_complete());
+    }
+
+    #[test]
+    #[should_panic = "generator resumed after completion"]
+    fn poll_resume_after_completion() {
+        let mut gn = pin!(page(0, 5));
+        let mut cx = Context::from_waker(Waker::noop());
+        assert_eq!(
+            gn.as_mut().poll_resume(&mut cx),
+            Poll::Ready(GeneratorState::Complete(None))
+        );
+        let _ = gn.as_mut().poll_resume(&mut cx);
+    }
+
+    #[test]
+    fn output_after_items() {
+        let mut gn = pin!(page(2, 1));
+        let mut cx = Context::from_waker(Waker::noop());
+        let mut items = Vec::new();
+        while let Poll::Ready(Some(item)) = gn.as_mut().poll_next(&mut cx) {
+            items.push(item);
+        }
+        assert_eq!(items, vec![10, 11]);
+        assert_eq!(gn.as_mut().take_output(), Some(Some(2)));
+        assert_eq!(gn.as_mut().take_output(), None);
+    }
+
+    #[generator(yield = u32)]
+    async fn all_pages() -> usize {
+        let mut cursor = Some(0);
+        let mut pages = 0;
+        while let Some(next) = cursor {
+            cursor = r#yield!(from page(1, next));
+            pages += 1;
+        }
+        pages
+    }
+
+    #[cfg(feature = "futures")]
+    #[tokio::test]
+    async fn delegate_completion() {
+        use futures_util::StreamExt;
+
+        let mut gn = pin!(all_pages());
+        assert_eq!(gn.as_mut().collect::<Vec<_>>().await, vec![0, 10, 20]);
+        assert_eq!(gn.take_output(), Some(3));
+    }
+
+    #[tokio::test]
+    async fn into_output_skips_items() {
+        assert_eq!(page(3, 2).into_output().await, None);
+        assert_eq!(all_pages().into_output().await, 3);
+    }
+}
@@ -0,0 +1,163 @@
+#![cfg_attr(feature = "nightly", feature(coroutines))]
+
+use core::pin::pin;
+use yaag::{GeneratorState, generator, gn, gn_type};
+
+fn chain<A: IntoIterator<Item = T>, B: IntoIterator<Item = T>, T>(a: A, b: B) -> gn_type!(T) {
+    gn!(move gen {
+        yield from a;
+        yield from b;
+    } -> T)
+}
+
+#[test]
+fn delegates_to_iterators() {
+    let gn = chain(vec![1, 2], 3..=5);
+    let gn = pin!(gn);
+    assert_eq!(gn.collect::<Vec<_>>(), vec![1, 2, 3, 4, 5]);
+}
+
+fn counted(items: &[u32]) -> gn_type!(u32, usize) {
+    gn!(move gen {
+        for &item in items {
+            yield item;
+        }
+        items.len()
+    } -> u32, usize)
+}
+
+#[test]
+fn delegates_to_generators() {
+    let gn = gn!(gen {
+        let first = yield from counted(&[1, 2]);
+        let second = yield from counted(&[3, 4, 5]);
+        first + second
+    } -> u32, usize);
+    let mut gn = pin!(gn);
+    for i in 1..=5 {
+        assert_eq!(gn.as_mut().resume(), GeneratorState::Yielded(i));
+    }
+    assert_eq!(gn.as_mut().resume(), GeneratorState::Complete(5));
+}
+
+enum Tree {
+    Leaf(i32),
+    Node(Vec<Tree>),
+}
+
+fn leaves(tree: &Tree) -> gn_type!(i32) {
+    gn!(move gen {
+        let mut stack = Vec::from([tree]);
+        while let Some(tree) = stack.pop() {
+            if let Tree::Node(children) = tree {
+                yield from children.iter().filter_map(|tree| match tree {
+                    Tree::Leaf(value) => Some(*value),
+                    Tree::Node(_) => None,
+                });
+                stack.extend(children.iter().rev().filter(|tree| matches!(tree, Tree::Node(_))));
+            }
+        }
+    } -> i32)
+}
+
+#[test]
+fn tree_walk() {
+    let tree = Tree::Node(vec![
+        Tree::Leaf(1),
+        Tree::Node(vec![
+            Tree::Leaf(3),
+            Tree::Node(vec![Tree::Leaf(5)]),
+            Tree::Leaf(4),
+        ]),
+        Tree::Leaf(2),
+        Tree::Node(vec![Tree::Node(vec![Tree::Leaf(6)])]),
+    ]);
+    let gn = leaves(&tree);
+    let gn = pin!(gn);
+    assert_eq!(gn.collect::<Vec<_>>(), vec![1, 2, 3, 4, 5, 6]);
+}
+
+#[test]
+fn nested_delegation() {
+    let gn = gn!(gen {
+        yield from {
+            yield 0;
+            yield from [1, 2];
+            [3]
+        };
+    } -> i32);
+    let gn = pin!(gn);
+    assert_eq!(gn.collect::<Vec<_>>(), vec![0, 1, 2, 3]);
+}
+
+#[test]
+fn from_is_still_a_value() {
+    let from = 42;
+    let gn = gn!(gen {
+        yield from;
+    } -> i32);
+    let gn = pin!(gn);
+    assert_eq!(gn.collect::<Vec<_>>(), vec![42]);
+}
+
+#[generator(yield = char)]
+fn letters(words: &[&str]) {
+    for word in words {
+        r#yield!(from word.chars());
+    }
+}
+
+#[test]
+fn attribute_delegation() {
+    let gn = letters(&["ab", "c"]);
+    let gn = pin!(gn);
+    assert_eq!(gn.collect::<String>(), "abc");
+}
+
+#[cfg(feature = "futures")]
+#[tokio::test]
+async fn async_delegation() {
+    use futures_util::{StreamExt, stream};
+    use std::time::Duration;
+
+    let inner = gn!(async gen {
+        for i in 3..5 {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            yield i;
+        }
+    } -> i32);
+    let gn = gn!(async gen {
+        yield from [0, 1];
+        yield from stream::iter([2]);
+        yield from inner;
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        yield from Vec::from([5]);
+    } -> i32);
+    assert_eq!(gn.collect::<Vec<_>>().await, vec![0, 1, 2, 3, 4, 5]);
+}
+
+#[test]
+fn variable_named_from() {
+    let from = 5u32;
+    let gn = gn!(gen {
+        yield from - 1;
+        yield from;
+        yield from.pow(2);
+    } -> u32);
+    assert_eq!(pin!(gn).collect::<Vec<_>>(), vec![4, 5, 25]);
+    let gn = gn!(gen { yield from as u64 + 1; } -> u64);
+    assert_eq!(pin!(gn).collect::<Vec<_>>(), vec![6]);
+}
+
+#[test]
+fn ambiguous_delegation() {
+    let mut items = 1..3;
+    let gn = gn!(gen {
+        // `yield from &items` would yield `from & items`
+        r#yield!(from &mut items);
+        yield from [3, 4];
+        yield from [];
+        r#yield!(from [5]);
+    } -> i32);
+    assert_eq!(pin!(gn).collect::<Vec<_>>(), vec![1, 2, 3, 4, 5]);
+}
@@ -0,0 +1,108 @@
+#![cfg_attr(feature = "nightly", feature(coroutines))]
+
+use core::pin::pin;
+use yaag::{generator, gn};
+
+fn parse_all<'a>(items: &'a [&'a str]) -> yaag::gn_type!(Result<i32, std::num::ParseIntError>) {
+    gn!(move try gen {
+        for item in items {
+            yield item.parse::<i32>()?;
+        }
+    } -> Result<i32, std::num::ParseIntError>)
+}
+
+#[test]
+fn all_ok() {
+    let gn = pin!(parse_all(&["1", "2", "3"]));
+    assert_eq!(gn.collect::<Result<Vec<_>, _>>(), Ok(vec![1, 2, 3]));
+}
+
+#[test]
+fn stops_on_error() {
+    let gn = parse_all(&["1", "x", "3"]);
+    let mut gn = pin!(gn);
+    assert_eq!(gn.next(), Some(Ok(1)));
+    assert!(gn.next().unwrap().is_err());
+    assert_eq!(gn.next(), None);
+    assert!(gn.is_complete());
+}
+
+#[derive(Debug, PartialEq)]
+enum Error {
+    Negative(i32),
+    Parse,
+}
+
+impl From<std::num::ParseIntError> for Error {
+    fn from(_: std::num::ParseIntError) -> Self {
+        Self::Parse
+    }
+}
+
+fn check(value: i32) -> Result<i32, Error> {
+    if value < 0 {
+        Err(Error::Negative(value))
+    } else {
+        Ok(value)
+    }
+}
+
+#[test]
+fn converts_errors() {
+    let gn = gn!(try gen {
+        yield check("1".parse::<i32>()?)?;
+        yield check("-2".parse()?)?;
+        yield 3;
+    } -> Result<i32, Error>);
+    let gn = pin!(gn);
+    assert_eq!(
+        gn.collect::<Vec<_>>(),
+        vec![Ok(1), Err(Error::Negative(-2))]
+    );
+}
+
+#[test]
+fn closures_keep_their_own_question_mark() {
+    let gn = gn!(try gen {
+        let parse = |s: &str| -> Result<i32, Error> { Ok(s.parse::<i32>()? * 2) };
+        yield parse("2")?;
+        yield parse("y")?;
+    } -> Result<i32, Error>);
+    let gn = pin!(gn);
+    assert_eq!(gn.collect::<Vec<_>>(), vec![Ok(4), Err(Error::Parse)]);
+}
+
+#[generator(yield = Result<u8, Error>, try)]
+fn bytes(items: Vec<i32>) {
+    for item in items {
+        r#yield!(u8::try_from(check(item)?).map_err(|_| Error::Parse)?);
+    }
+}
+
+#[test]
+fn attribute_try() {
+    let gn = pin!(bytes(vec![1, 300, 2]));
+    assert_eq!(gn.collect::<Vec<_>>(), vec![Ok(1), Err(Error::Parse)]);
+}
+
+#[cfg(feature = "futures")]
+#[tokio::test]
+async fn async_try() {
+    use futures_util::StreamExt;
+
+    async fn fetch(i: i32) -> Result<i32, Error> {
+        tokio::task::yield_now().await;
+        check(i)
+    }
+
+    let gn = gn!(async try gen {
+        for i in [1, 0, -1, 2] {
+            yield fetch(i).await?;
+        }
+    } -> Result<i32, Error>);
+    let gn = pin!(gn);
+    assert_eq!(
+        gn.collect::<Vec<_>>().await,
+        vec![Ok(1), Ok(0), Err(Error::Negative(-1))]
+    );
+}
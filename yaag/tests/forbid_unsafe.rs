@@ -0,0 +1,60 @@
+#![forbid(unsafe_code)]
+#![cfg_attr(feature = "nightly", feature(coroutines))]
+
+use core::pin::pin;
+use yaag::{GeneratorState, LendingIterator, generator, gn};
+
+#[generator(yield = u32)]
+fn counter(n: u32) {
+    for i in 0..n {
+        r#yield!(i);
+    }
+}
+
+#[test]
+fn sync_generators() {
+    let gn = gn!(gen {
+        yield from counter(2);
+        yield 2;
+    } -> u32);
+    assert_eq!(pin!(gn).collect::<Vec<_>>(), vec![0, 1, 2]);
+
+    let gn = gn!(gen(first: i32) {
+        let second = yield first;
+        second * 2
+    } -> i32, i32);
+    let mut gn = pin!(gn);
+    assert_eq!(gn.as_mut().resume_with(1), GeneratorState::Yielded(1));
+    assert_eq!(gn.as_mut().resume_with(2), GeneratorState::Complete(4));
+
+    let gn = gn!(try gen {
+        yield "1".parse::<i32>()?;
+        yield "x".parse::<i32>()?;
+    } -> Result<i32, core::num::ParseIntError>);
+    assert_eq!(pin!(gn).filter(Result::is_ok).count(), 1);
+}
+
+#[test]
+fn lending_generators() {
+    let gn = gn!(lending gen {
+        let mut buf = [0u8; 2];
+        buf[0] = 1;
+        yield &mut buf[..];
+    } -> &'_ mut [u8]);
+    let mut gn = pin!(gn);
+    assert_eq!(gn.next().map(|buf| buf[0]), Some(1));
+    assert!(gn.next().is_none());
+}
+
+#[cfg(feature = "futures")]
+#[tokio::test]
+async fn async_generators() {
+    use futures_util::StreamExt;
+
+    let gn = gn!(async gen {
+        tokio::task::yield_now().await;
+        yield 1;
+        yield from [2, 3];
+    } -> i32);
+    assert_eq!(gn.collect::<Vec<_>>().await, vec![1, 2, 3]);
+}
@@ -0,0 +1,75 @@
+#![cfg_attr(feature = "nightly", feature(coroutines))]
+
+use core::pin::pin;
+use yaag::{GeneratorState, gn};
+
+#[test]
+fn next_after_completion() {
+    let gn = gn!(gen {
+        yield 1;
+    } -> i32);
+    let mut gn = pin!(gn);
+    assert_eq!(gn.next(), Some(1));
+    assert!(!gn.is_complete());
+    assert_eq!(gn.next(), None);
+    assert!(gn.is_complete());
+    assert_eq!(gn.next(), None);
+    assert_eq!(gn.next(), None);
+}
+
+#[test]
+fn zip_with_shorter() {
+    let gn = gn!(gen {
+        yield 1;
+        yield 2;
+    } -> i32);
+    let mut gn = pin!(gn);
+    let zipped = gn.as_mut().zip([10, 20, 30]).collect::<Vec<_>>();
+    assert_eq!(zipped, vec![(1, 10), (2, 20)]);
+    // generator was already polled past its end
+    assert_eq!(gn.zip([40]).next(), None);
+}
+
+#[test]
+fn retry_loop() {
+    let gn = gn!(gen {
+        yield 'a';
+    } -> char);
+    let mut gn = pin!(gn);
+    let mut seen = Vec::new();
+    for _ in 0..3 {
+        seen.extend(gn.as_mut());
+    }
+    assert_eq!(seen, vec!['a']);
+}
+
+#[test]
+#[should_panic = "generator resumed after completion"]
+fn resume_after_completion() {
+    let gn = gn!(gen {
+        yield 1;
+        "done"
+    } -> i32, &str);
+    let mut gn = pin!(gn);
+    assert_eq!(gn.as_mut().resume(), GeneratorState::Yielded(1));
+    assert_eq!(gn.as_mut().resume(), GeneratorState::Complete("done"));
+    let _ = gn.as_mut().resume();
+}
+
+#[cfg(feature = "futures")]
+#[tokio::test]
+async fn stream_after_completion() {
+    use futures_core::stream::FusedStream;
+    use futures_util::StreamExt;
+
+    let gn = gn!(async gen {
+        tokio::task::yield_now().await;
+        yield 1;
+    } -> i32);
+    let mut gn = pin!(gn);
+    assert_eq!(gn.next().await, Some(1));
+    assert!(!gn.is_terminated());
+    assert_eq!(gn.next().await, None);
+    assert!(gn.is_terminated());
+    assert_eq!(gn.next().await, None);
+}
@@ -0,0 +1,97 @@
+#![cfg_attr(feature = "nightly", feature(coroutines))]
+
+use core::pin::{Pin, pin};
+use yaag::{Generator, GeneratorExt, GeneratorState, gn};
+
+/// Hand-written generator, counting down to zero and completing with the number of steps
+struct Countdown(u32, u32);
+
+impl Generator for Countdown {
+    type Yield = u32;
+    type Return = u32;
+
+    fn resume(self: Pin<&mut Self>) -> GeneratorState<u32, u32> {
+        let this = self.get_mut();
+        if this.0 == 0 {
+            GeneratorState::Complete(this.1)
+        } else {
+            this.0 -= 1;
+            this.1 += 1;
+            GeneratorState::Yielded(this.0)
+        }
+    }
+}
+
+fn drain<G: Generator>(generator: G) -> (Vec<G::Yield>, G::Return) {
+    let mut generator = pin!(generator);
+    let mut items = Vec::new();
+    loop {
+        match generator.as_mut().resume() {
+            GeneratorState::Yielded(item) => items.push(item),
+            GeneratorState::Complete(ret) => return (items, ret),
+        }
+    }
+}
+
+#[test]
+fn generic_over_generators() {
+    assert_eq!(drain(Countdown(3, 0)), (vec![2, 1, 0], 3));
+    let gn = gn!(gen {
+        yield 'a';
+        yield 'b';
+        "done"
+    } -> char, &str);
+    assert_eq!(drain(gn), (vec!['a', 'b'], "done"));
+}
+
+#[test]
+fn map_and_filter() {
+    let gn = gn!(gen {
+        for i in 0..6 {
+            yield i;
+        }
+        6
+    } -> i32, i32);
+    let mapped = gn.filter(|i| i % 2 == 0).map(|i| i * 10);
+    assert_eq!(drain(mapped), (vec![0, 20, 40], 6));
+}
+
+#[test]
+fn chain() {
+    let first = gn!(gen {
+        yield 1;
+        "first"
+    } -> u32, &str);
+    let chained = first.chain(Countdown(2, 0));
+    assert_eq!(drain(chained), (vec![1, 1, 0], ("first", 2)));
+}
+
+#[test]
+fn into_iter() {
+    assert_eq!(
+        Countdown(3, 0)
+            .map(|i| i + 1)
+            .into_iter()
+            .collect::<Vec<_>>(),
+        vec![3, 2, 1]
+    );
+    let gn = gn!(gen {
+        yield 1;
+        yield 2;
+    } -> i32);
+    let gn = pin!(gn);
+    // pinned generator is an `Iterator` too
+    let mut iter = GeneratorExt::into_iter(gn);
+    assert_eq!(iter.by_ref().sum::<i32>(), 3);
+    assert_eq!(iter.next(), None);
+}
+
+#[test]
+fn delegates_to_any_generator() {
+    let gn = gn!(gen {
+        let steps = yield from Countdown(2, 0);
+        yield steps + 100;
+    } -> u32);
+    let gn = pin!(gn);
+    assert_eq!(gn.collect::<Vec<_>>(), vec![1, 0, 102]);
+}
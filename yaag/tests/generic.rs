@@ -1,5 +1,7 @@
+#![cfg_attr(feature = "nightly", feature(coroutines))]
+
 use core::pin::pin;
-#[cfg(feature = "async")]
+#[cfg(feature = "futures")]
 use yaag::async_gn_type;
 use yaag::{gn, gn_type};
 
@@ -21,7 +23,7 @@ fn once_works_box() {
     assert_eq!(once.collect::<Vec<_>>(), vec![Box::new(42)]);
 }
 
-#[cfg(feature = "async")]
+#[cfg(feature = "futures")]
 fn once_async<T>(t: T) -> async_gn_type!(T) {
     use std::time::Duration;
 
@@ -31,7 +33,7 @@ fn once_async<T>(t: T) -> async_gn_type!(T) {
     } -> T)
 }
 
-#[cfg(feature = "async")]
+#[cfg(feature = "futures")]
 #[tokio::test]
 async fn once_async_works_i32() {
     use futures_util::StreamExt;
@@ -40,7 +42,7 @@ async fn once_async_works_i32() {
     assert_eq!(once.collect::<Vec<_>>().await, vec![42]);
 }
 
-#[cfg(feature = "async")]
+#[cfg(feature = "futures")]
 #[tokio::test]
 async fn once_async_works_box() {
     use futures_util::StreamExt;
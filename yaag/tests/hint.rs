@@ -0,0 +1,229 @@
+#![cfg_attr(feature = "nightly", feature(coroutines))]
+
+use core::pin::pin;
+use yaag::{Len, generator, gn, gn_type};
+
+fn squares(items: &[u32]) -> gn_type!(u32, (), (), Len) {
+    gn!(move gen {
+        for item in items {
+            yield item * item;
+        }
+    } -> u32, len = items.len())
+}
+
+#[test]
+fn exact_len() {
+    let gn = squares(&[1, 2, 3]);
+    let mut gn = pin!(gn);
+    assert_eq!(gn.len(), 3);
+    assert_eq!(gn.next(), Some(1));
+    assert_eq!(gn.len(), 2);
+    assert_eq!(gn.size_hint(), (2, Some(2)));
+    assert_eq!(gn.collect::<Vec<_>>(), vec![4, 9]);
+}
+
+#[test]
+fn default_hint() {
+    let gn = gn!(gen {
+        yield 1;
+    } -> i32);
+    let gn = pin!(gn);
+    assert_eq!(gn.size_hint(), (0, None));
+}
+
+#[test]
+fn bounds() {
+    let hint = 10;
+    let gn = gn!(gen {
+        for i in 0..hint {
+            if i % 2 == 0 {
+                yield i;
+            }
+        }
+    } -> i32, size_hint = (0, Some(hint as usize)));
+    let mut gn = pin!(gn);
+    assert_eq!(gn.size_hint(), (0, Some(10)));
+    assert_eq!(gn.next(), Some(0));
+    assert_eq!(gn.size_hint(), (0, Some(9)));
+    assert_eq!(gn.by_ref().count(), 4);
+    assert_eq!(gn.size_hint(), (0, Some(0)));
+}
+
+#[test]
+fn with_completion_value() {
+    let gn = gn!(gen {
+        yield 1;
+        yield 2;
+        "done"
+    } -> i32, &str, len = 2);
+    let gn = pin!(gn);
+    assert_eq!(gn.len(), 2);
+}
+
+#[test]
+fn updated_by_body() {
+    let records = [3, 10, 20, 30];
+    let gn = gn!(gen {
+        // count is only known once the header record is read
+        let (count, rest) = records.split_first().unwrap();
+        size_hint!(*count as usize);
+        for record in rest {
+            yield *record;
+        }
+    } -> i32, len = 0);
+    let mut gn = pin!(gn);
+    assert_eq!(gn.len(), 0);
+    assert_eq!(gn.next(), Some(10));
+    assert_eq!(gn.len(), 2);
+    assert_eq!(gn.collect::<Vec<_>>(), vec![20, 30]);
+}
+
+#[test]
+fn bounds_updated_by_body() {
+    let gn = gn!(gen {
+        yield 1;
+        size_hint!((2, None));
+        yield 2;
+        yield 3;
+    } -> i32, size_hint = (1, Some(1)));
+    let mut gn = pin!(gn);
+    assert_eq!(gn.next(), Some(1));
+    assert_eq!(gn.size_hint(), (0, Some(0)));
+    assert_eq!(gn.next(), Some(2));
+    assert_eq!(gn.size_hint(), (1, None));
+    assert_eq!(gn.by_ref().count(), 1);
+}
+
+#[test]
+fn error_is_the_last_item() {
+    fn parse(items: &[&str]) -> gn_type!(Result<i32, core::num::ParseIntError>, (), (), Len) {
+        gn!(move try gen {
+            for item in items {
+                yield item.parse::<i32>()?;
+            }
+        } -> Result<i32, core::num::ParseIntError>, len = items.len())
+    }
+
+    let mut gn = pin!(parse(&["1", "x", "3"]));
+    assert_eq!(gn.next(), Some(Ok(1)));
+    assert!(matches!(gn.next(), Some(Err(_))));
+    assert_eq!(gn.len(), 0);
+    assert_eq!(gn.next(), None);
+}
+
+#[cfg(debug_assertions)]
+#[test]
+#[should_panic = "generator completed with 1 of its declared `len` items left"]
+fn too_few_items() {
+    let gn = gn!(gen {
+        yield 1;
+    } -> i32, len = 2);
+    pin!(gn).for_each(drop);
+}
+
+#[cfg(debug_assertions)]
+#[test]
+fn fused_after_too_few_items() {
+    use std::panic::{AssertUnwindSafe, catch_unwind};
+
+    let gn = gn!(gen {
+        yield 1;
+    } -> i32, len = 2);
+    let mut gn = pin!(gn);
+    assert!(catch_unwind(AssertUnwindSafe(|| gn.by_ref().for_each(drop))).is_err());
+    assert!(gn.is_complete());
+    assert!(!gn.is_poisoned());
+    assert_eq!(gn.next(), None);
+}
+
+#[cfg(all(debug_assertions, feature = "futures"))]
+#[tokio::test]
+async fn stream_fused_after_too_few_items() {
+    use futures_util::{FutureExt, StreamExt};
+    use std::panic::AssertUnwindSafe;
+
+    let gn = gn!(async gen {
+        yield 1;
+    } -> i32, len = 2);
+    let mut gn = pin!(gn);
+    assert_eq!(gn.next().await, Some(1));
+    assert!(AssertUnwindSafe(gn.next()).catch_unwind().await.is_err());
+    assert!(gn.is_complete());
+    assert_eq!(gn.next().await, None);
+}
+
+#[cfg(debug_assertions)]
+#[test]
+#[should_panic = "generator yielded more items than its declared `len`"]
+fn too_many_items() {
+    let gn = gn!(gen {
+        yield 1;
+        yield 2;
+    } -> i32, len = 1);
+    pin!(gn).for_each(drop);
+}
+
+#[generator(yield = usize, len = n)]
+fn count(n: usize) {
+    for i in 0..n {
+        r#yield!(i);
+    }
+}
+
+#[test]
+fn attribute_len() {
+    let gn = pin!(count(4));
+    assert_eq!(gn.len(), 4);
+    assert_eq!(gn.collect::<Vec<_>>(), vec![0, 1, 2, 3]);
+}
+
+#[generator(yield = u8, size_hint = (0, None))]
+fn bytes(text: &str) {
+    size_hint!((text.len(), Some(text.len())));
+    for byte in text.bytes() {
+        r#yield!(byte);
+    }
+}
+
+#[test]
+fn attribute_update() {
+    let mut gn = pin!(bytes("abc"));
+    assert_eq!(gn.next(), Some(b'a'));
+    assert_eq!(gn.size_hint(), (2, Some(2)));
+}
+
+#[cfg(feature = "futures")]
+#[tokio::test]
+async fn stream_hint() {
+    use futures_util::StreamExt;
+
+    let gn = gn!(async gen {
+        for i in 0..3 {
+            tokio::task::yield_now().await;
+            yield i;
+        }
+    } -> i32, len = 3);
+    let mut gn = pin!(gn);
+    assert_eq!(gn.size_hint(), (3, Some(3)));
+    assert_eq!(gn.next().await, Some(0));
+    assert_eq!(gn.size_hint(), (2, Some(2)));
+    assert_eq!(gn.collect::<Vec<_>>().await, vec![1, 2]);
+}
+
+#[cfg(feature = "futures")]
+#[tokio::test]
+async fn stream_hint_updated_by_body() {
+    use futures_util::StreamExt;
+
+    let gn = gn!(async gen {
+        let count = async { 2 }.await;
+        size_hint!(count);
+        for i in 0..count {
+            yield i;
+        }
+    } -> usize, len = 0);
+    let mut gn = pin!(gn);
+    assert_eq!(gn.next().await, Some(0));
+    assert_eq!(gn.size_hint(), (1, Some(1)));
+    assert_eq!(gn.collect::<Vec<_>>().await, vec![1]);
+}
@@ -0,0 +1,65 @@
+#![cfg_attr(feature = "nightly", feature(coroutines))]
+
+use core::pin::pin;
+use yaag::{GeneratorState, gn};
+
+#[test]
+fn omitted_item_type() {
+    let gn = gn!(gen {
+        yield 1u8;
+        yield 2;
+    });
+    assert_eq!(pin!(gn).collect::<Vec<_>>(), vec![1u8, 2]);
+}
+
+#[test]
+fn inferred_item_type() {
+    let words = ["a", "b"];
+    let gn = gn!(gen {
+        for word in words {
+            yield word.to_uppercase();
+        }
+    } -> _);
+    assert_eq!(pin!(gn).collect::<Vec<_>>(), vec!["A", "B"]);
+}
+
+#[test]
+fn inferred_with_completion_and_hint() {
+    let gn = gn!(gen {
+        yield 'a';
+        "done"
+    } -> _, &str, len = 1);
+    let mut gn = pin!(gn);
+    assert_eq!(gn.len(), 1);
+    assert_eq!(gn.as_mut().resume(), GeneratorState::Yielded('a'));
+    assert_eq!(gn.as_mut().resume(), GeneratorState::Complete("done"));
+}
+
+#[test]
+fn inferred_from_usage() {
+    let gn = gn!(gen {
+        yield Default::default();
+    });
+    let items: Vec<String> = pin!(gn).collect();
+    assert_eq!(items, vec![String::new()]);
+}
+
+#[test]
+fn inferred_from_delegate() {
+    let gn = gn!(gen {
+        yield from [1i64, 2];
+    });
+    assert_eq!(pin!(gn).sum::<i64>(), 3);
+}
+
+#[cfg(feature = "futures")]
+#[tokio::test]
+async fn inferred_async() {
+    use futures_util::StreamExt;
+
+    let gn = gn!(async gen {
+        tokio::task::yield_now().await;
+        yield 1u16;
+    });
+    assert_eq!(gn.collect::<Vec<_>>().await, vec![1]);
+}
@@ -1,5 +1,7 @@
+#![cfg_attr(feature = "nightly", feature(coroutines))]
+
 use core::pin::pin;
-#[cfg(feature = "async")]
+#[cfg(feature = "futures")]
 use yaag::async_gn_type;
 use yaag::{gn, gn_type};
 
@@ -26,7 +28,7 @@ fn from_fn_works() {
     assert_eq!(gn.collect::<Vec<_>>(), (1..=10).collect::<Vec<_>>());
 }
 
-#[cfg(feature = "async")]
+#[cfg(feature = "futures")]
 fn from_fn_async<T, F: FnMut() -> Option<T>>(mut f: F) -> async_gn_type!(T) {
     use std::time::Duration;
 
@@ -38,7 +40,7 @@ fn from_fn_async<T, F: FnMut() -> Option<T>>(mut f: F) -> async_gn_type!(T) {
     } -> T)
 }
 
-#[cfg(feature = "async")]
+#[cfg(feature = "futures")]
 #[tokio::test]
 async fn from_fn_works_async() {
     use futures_util::StreamExt;
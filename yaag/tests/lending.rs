@@ -0,0 +1,89 @@
+use core::pin::pin;
+use yaag::{LendingIterator, gn, lending_gn_type};
+
+fn records(data: &[u8]) -> lending_gn_type!(yaag::Mut<[u8]>) {
+    gn!(move lending gen {
+        let mut scratch = Vec::new();
+        for &byte in data {
+            if byte == b',' {
+                yield &mut scratch[..];
+                scratch.clear();
+            } else {
+                scratch.push(byte);
+            }
+        }
+        if !scratch.is_empty() {
+            yield &mut scratch[..];
+        }
+    } -> &'_ mut [u8])
+}
+
+#[test]
+fn reuses_buffer() {
+    let gn = records(b"ab,cde,f");
+    let mut gn = pin!(gn);
+    let mut seen = Vec::new();
+    while let Some(record) = gn.next() {
+        record.make_ascii_uppercase();
+        seen.push(String::from_utf8(record.to_vec()).unwrap());
+    }
+    assert_eq!(seen, vec!["AB", "CDE", "F"]);
+    assert!(gn.is_complete());
+    assert!(gn.next().is_none());
+}
+
+#[test]
+fn shared_references() {
+    let gn = gn!(lending gen {
+        let mut line = String::new();
+        for word in ["a", "b", "c"] {
+            line.push_str(word);
+            yield line.as_str();
+        }
+    } -> &str);
+    let mut gn = pin!(gn);
+    assert_eq!(gn.next(), Some("a"));
+    assert_eq!(gn.next(), Some("ab"));
+    assert_eq!(gn.next(), Some("abc"));
+    assert_eq!(gn.next(), None);
+}
+
+struct Window<'a> {
+    index: usize,
+    values: &'a [i32],
+}
+
+#[test]
+fn custom_family() {
+    let gn = gn!(lending gen {
+        let mut values = Vec::new();
+        for index in 0..3 {
+            values.push(index as i32 * 10);
+            yield Window { index, values: &values };
+        }
+    } -> Window<'_>);
+    let mut gn = pin!(gn);
+    let mut seen = Vec::new();
+    while let Some(Window { index, values }) = gn.next() {
+        seen.push((index, values.iter().sum::<i32>()));
+    }
+    assert_eq!(seen, vec![(0, 0), (1, 10), (2, 30)]);
+}
+
+#[test]
+fn tuple_family() {
+    let gn = gn!(lending gen {
+        let mut buf = [0u8; 4];
+        for len in 1..=2 {
+            buf[..len].fill(len as u8);
+            yield (len, &mut buf[..len]);
+        }
+    } -> (usize, &'_ mut [u8]));
+    let mut gn = pin!(gn);
+    let (len, bytes) = gn.next().unwrap();
+    assert_eq!((len, &*bytes), (1, &[1][..]));
+    bytes[0] = 7;
+    let (len, bytes) = gn.next().unwrap();
+    assert_eq!((len, &*bytes), (2, &[2, 2][..]));
+    assert!(gn.next().is_none());
+}
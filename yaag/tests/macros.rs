@@ -0,0 +1,116 @@
+#![cfg_attr(feature = "nightly", feature(coroutines))]
+
+use core::{fmt::Write, pin::pin};
+use yaag::{generator, gn};
+
+#[test]
+fn std_macros() {
+    let gn = gn!(gen {
+        let items = vec![1, 2, 3];
+        assert_eq!(items.len(), 3, "length is {}", items.len());
+        for item in items {
+            yield format!("{item}");
+        }
+        let mut out = String::new();
+        write!(out, "{}-{}", 4, 5).unwrap();
+        yield out;
+        println!("done");
+    } -> String);
+    let gn = pin!(gn);
+    assert_eq!(gn.collect::<Vec<_>>(), vec!["1", "2", "3", "4-5"]);
+}
+
+#[test]
+fn yields_inside_macros() {
+    let gn = gn!(gen(i32) {
+        let arg = yield 1;
+        let pair = vec![yield arg, yield arg * 2];
+        yield format!("{:?}", pair).len() as i32;
+        assert!(matches!(yield 0, 7), "seven");
+        let mut v = vec![yield 100; 2];
+        v.push(0);
+        yield v.len() as i32;
+    } -> i32);
+    let mut gn = pin!(gn);
+    let mut resumed = Vec::new();
+    for arg in [0, 10, 20, 30, 0, 7, 5, 0] {
+        match gn.as_mut().resume_with(arg) {
+            yaag::GeneratorState::Yielded(item) => resumed.push(item),
+            yaag::GeneratorState::Complete(()) => break,
+        }
+    }
+    // [20, 30] is 8 characters long
+    assert_eq!(resumed, vec![1, 10, 20, 8, 0, 100, 3]);
+}
+
+#[test]
+fn pin_macro() {
+    let gn = gn!(gen {
+        let mut inner = core::pin::pin!(gn!(gen { yield 1; yield 2; } -> i32));
+        while let yaag::GeneratorState::Yielded(item) = inner.as_mut().resume() {
+            yield item;
+        }
+    } -> i32);
+    let gn = pin!(gn);
+    assert_eq!(gn.collect::<Vec<_>>(), vec![1, 2]);
+}
+
+macro_rules! double {
+    ($e:expr) => {
+        $e * 2
+    };
+}
+
+macro_rules! log {
+    ($($t:tt)*) => {{
+        let _ = format!($($t)*);
+    }};
+}
+
+#[test]
+fn allowed_user_macros() {
+    let gn = gn!(allow_macros(double, log) gen {
+        for i in 0..3 {
+            log!("{i}");
+            yield double!(i);
+        }
+        yield double!({
+            yield 10;
+            5
+        });
+    } -> i32);
+    let gn = pin!(gn);
+    assert_eq!(gn.collect::<Vec<_>>(), vec![0, 2, 4, 10, 10]);
+}
+
+#[generator(yield = i32, allow_macros(double))]
+fn doubled(n: i32) {
+    for i in 0..n {
+        r#yield!(double!(i));
+    }
+}
+
+#[test]
+fn attribute_allowed_macros() {
+    let gn = doubled(3);
+    let gn = pin!(gn);
+    assert_eq!(gn.collect::<Vec<_>>(), vec![0, 2, 4]);
+}
+
+#[cfg(feature = "futures")]
+#[tokio::test]
+async fn async_macros() {
+    use futures_util::StreamExt;
+    use std::time::Duration;
+
+    let gn = gn!(async gen {
+        let wait = async |v: i32| {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            v
+        };
+        yield format!("{}", wait(1).await);
+        assert_eq!(wait(2).await, 2);
+        yield format!("{}", wait(3).await);
+    } -> String);
+    assert_eq!(gn.collect::<Vec<_>>().await, vec!["1", "3"]);
+}
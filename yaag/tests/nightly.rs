@@ -0,0 +1,81 @@
+#![cfg(feature = "nightly")]
+#![feature(coroutines, coroutine_clone, coroutine_trait)]
+
+use core::{
+    ops::{Coroutine, CoroutineState},
+    pin::pin,
+};
+use yaag::{generator, gn};
+
+#[test]
+fn is_coroutine() {
+    let mut gn = pin!(gn!(gen(first: u32) {
+        let second = yield first;
+        second * 2
+    } -> u32, u32));
+    assert_eq!(gn.as_mut().resume(1), CoroutineState::Yielded(1));
+    assert_eq!(gn.as_mut().resume(2), CoroutineState::Complete(4));
+}
+
+#[test]
+fn async_backend_is_coroutine_too() {
+    // closable generators stay on the `async` backend
+    let mut gn = pin!(gn!(closable gen {
+        let _ = yield 1;
+    } -> i32));
+    // inherent `resume` takes no arguments
+    assert_eq!(
+        Coroutine::resume(gn.as_mut(), ()),
+        CoroutineState::Yielded(1)
+    );
+    assert_eq!(
+        Coroutine::resume(gn.as_mut(), ()),
+        CoroutineState::Complete(())
+    );
+}
+
+#[test]
+fn borrows_across_yields() {
+    let gn = gn!(gen {
+        let items = [1, 2, 3];
+        for item in &items {
+            yield *item;
+        }
+    } -> i32);
+    assert_eq!(pin!(gn).collect::<Vec<_>>(), vec![1, 2, 3]);
+}
+
+#[test]
+fn movable_is_clone() {
+    let mut gn = gn!(movable gen {
+        for item in 0..3 {
+            yield item;
+        }
+    } -> i32);
+    assert_eq!(gn.next(), Some(0));
+    let copy = gn.clone();
+    assert_eq!(gn.collect::<Vec<_>>(), vec![1, 2]);
+    assert_eq!(copy.collect::<Vec<_>>(), vec![1, 2]);
+}
+
+#[generator(yield = u32, movable)]
+fn countdown(from: u32) {
+    for item in (0..from).rev() {
+        r#yield!(item);
+    }
+}
+
+#[test]
+fn movable_attribute() {
+    assert_eq!(pin!(countdown(3)).collect::<Vec<_>>(), vec![2, 1, 0]);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn boxed() {
+    let gns: Vec<yaag::BoxedGn<'static, i32>> = vec![
+        gn!(gen { yield 1; } -> i32).boxed(),
+        gn!(closable gen { let _ = yield 2; } -> i32).boxed(),
+    ];
+    assert_eq!(gns.into_iter().flatten().collect::<Vec<_>>(), vec![1, 2]);
+}
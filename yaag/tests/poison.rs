@@ -0,0 +1,52 @@
+#![cfg_attr(feature = "nightly", feature(coroutines))]
+
+use core::pin::pin;
+use std::panic::{AssertUnwindSafe, catch_unwind};
+use yaag::gn;
+
+#[test]
+fn poisoned_after_panic() {
+    let gn = gn!(gen {
+        yield 1;
+        panic!("boom");
+    } -> i32);
+    let mut gn = pin!(gn);
+    assert_eq!(gn.next(), Some(1));
+    assert!(!gn.is_poisoned());
+    assert!(catch_unwind(AssertUnwindSafe(|| gn.next())).is_err());
+    assert!(gn.is_poisoned());
+    assert!(!gn.is_complete());
+    // not re-entered
+    assert_eq!(gn.next(), None);
+    assert_eq!(gn.next(), None);
+}
+
+#[test]
+#[should_panic = "generator resumed after its body panicked"]
+fn resume_after_panic() {
+    let gn = gn!(gen {
+        panic!("boom");
+    } -> i32, ());
+    let mut gn = pin!(gn);
+    let _ = catch_unwind(AssertUnwindSafe(|| gn.as_mut().resume()));
+    let _ = gn.as_mut().resume();
+}
+
+#[cfg(feature = "futures")]
+#[tokio::test]
+async fn stream_poisoned_after_panic() {
+    use futures_core::stream::FusedStream;
+    use futures_util::{FutureExt, StreamExt};
+
+    let gn = gn!(async gen {
+        yield 1;
+        tokio::task::yield_now().await;
+        panic!("boom");
+    } -> i32);
+    let mut gn = pin!(gn);
+    assert_eq!(gn.next().await, Some(1));
+    assert!(AssertUnwindSafe(gn.next()).catch_unwind().await.is_err());
+    assert!(gn.is_poisoned());
+    assert!(gn.is_terminated());
+    assert_eq!(gn.next().await, None);
+}
@@ -0,0 +1,141 @@
+#![cfg_attr(feature = "nightly", feature(coroutines))]
+
+use core::pin::pin;
+use yaag::{GeneratorState, gn};
+
+#[test]
+fn match_arms() {
+    let gn = gn!(gen {
+        for i in 0..4 {
+            match i {
+                0 => yield 10,
+                1 => {
+                    yield 20;
+                    yield 21;
+                }
+                _ if i % 2 == 0 => yield 30,
+                _ => {}
+            }
+        }
+    } -> i32);
+    let gn = pin!(gn);
+    assert_eq!(gn.collect::<Vec<_>>(), vec![10, 20, 21, 30]);
+}
+
+#[test]
+fn match_guards() {
+    let gn = gn!(gen(first: bool) {
+        let mut value = first;
+        loop {
+            match () {
+                () if value => value = yield 1,
+                () if { yield 2 } => value = true,
+                () => break,
+            }
+        }
+    } -> i32);
+    let mut gn = pin!(gn);
+    assert_eq!(gn.as_mut().resume_with(false), GeneratorState::Yielded(2));
+    assert_eq!(gn.as_mut().resume_with(true), GeneratorState::Yielded(1));
+    assert_eq!(gn.as_mut().resume_with(false), GeneratorState::Yielded(2));
+    assert_eq!(gn.as_mut().resume_with(false), GeneratorState::Complete(()));
+}
+
+#[derive(Debug, PartialEq)]
+struct Pair {
+    first: i32,
+    second: i32,
+}
+
+#[test]
+fn struct_fields() {
+    let gn = gn!(gen(first: i32) {
+        let pair = Pair {
+            first,
+            second: yield 0,
+        };
+        yield pair.first + pair.second;
+    } -> i32);
+    let mut gn = pin!(gn);
+    assert_eq!(gn.as_mut().resume_with(1), GeneratorState::Yielded(0));
+    assert_eq!(gn.as_mut().resume_with(2), GeneratorState::Yielded(3));
+}
+
+#[test]
+fn let_else() {
+    let gn = gn!(gen {
+        for item in [Some(1), None, Some(3)] {
+            let Some(value) = item else {
+                yield -1;
+                continue;
+            };
+            yield value;
+        }
+    } -> i32);
+    let gn = pin!(gn);
+    assert_eq!(gn.collect::<Vec<_>>(), vec![1, -1, 3]);
+}
+
+#[test]
+fn assignment_target() {
+    let gn = gn!(gen(first: usize) {
+        let mut values = [0; 3];
+        values[first] = 10;
+        values[yield 0] = 20;
+        yield values.iter().sum::<i32>();
+    } -> i32);
+    let mut gn = pin!(gn);
+    assert_eq!(gn.as_mut().resume_with(0), GeneratorState::Yielded(0));
+    assert_eq!(gn.as_mut().resume_with(1), GeneratorState::Yielded(30));
+}
+
+#[cfg(feature = "futures")]
+#[tokio::test]
+async fn async_arms_and_fields() {
+    use futures_util::StreamExt;
+
+    async fn double(value: i32) -> i32 {
+        tokio::task::yield_now().await;
+        value * 2
+    }
+
+    let gn = gn!(async gen {
+        for i in 0..3 {
+            match i {
+                0 => yield double(i).await,
+                n if double(n).await > 2 => {
+                    let pair = Pair {
+                        first: double(n).await,
+                        second: n,
+                    };
+                    yield pair.first + pair.second;
+                }
+                n => yield n,
+            }
+        }
+    } -> i32);
+    let gn = pin!(gn);
+    assert_eq!(gn.collect::<Vec<_>>().await, vec![0, 1, 6]);
+}
+
+#[test]
+fn nested_generators() {
+    let gn = gn!(gen {
+        let inner = |n: i32| gn!(move gen {
+            for i in 0..n {
+                yield i;
+            }
+        } -> i32);
+
+        #[yaag::generator(yield = i32)]
+        fn twice(value: i32) {
+            r#yield!(value);
+            r#yield!(value);
+        }
+
+        yield from inner(2);
+        yield from twice(5);
+    } -> i32);
+    let gn = pin!(gn);
+    assert_eq!(gn.collect::<Vec<_>>(), vec![0, 1, 5, 5]);
+}
@@ -1,3 +1,5 @@
+#![cfg_attr(feature = "nightly", feature(coroutines))]
+
 use std::pin::pin;
 
 use yaag::gn;
@@ -9,7 +11,7 @@ fn empty() {
     assert_eq!(gn.collect::<Vec<_>>(), Vec::<i32>::new());
 }
 
-#[cfg(feature = "async")]
+#[cfg(feature = "futures")]
 #[tokio::test]
 async fn empty_async() {
     use futures_util::StreamExt;
@@ -26,7 +28,7 @@ fn single() {
     assert_eq!(gn.collect::<Vec<_>>(), vec![42]);
 }
 
-#[cfg(feature = "async")]
+#[cfg(feature = "futures")]
 #[tokio::test]
 async fn single_async() {
     use futures_util::StreamExt;
@@ -54,7 +56,7 @@ fn generates_1_10() {
     assert_eq!(it.collect::<Vec<_>>(), gn.collect::<Vec<_>>());
 }
 
-#[cfg(feature = "async")]
+#[cfg(feature = "futures")]
 #[tokio::test]
 async fn generates_1_10_async() {
     use std::time::Duration;
@@ -87,7 +89,7 @@ fn captures_ref() {
     assert_eq!(it.collect::<Vec<_>>(), gn.collect::<Vec<_>>());
 }
 
-#[cfg(feature = "async")]
+#[cfg(feature = "futures")]
 #[tokio::test]
 async fn captures_ref_async() {
     use futures_util::StreamExt;
@@ -0,0 +1,106 @@
+#![cfg_attr(feature = "nightly", feature(coroutines))]
+
+use core::pin::pin;
+use yaag::{GeneratorState, generator, gn, gn_type};
+
+enum Cmd {
+    Add(i32),
+    Reset,
+    Stop,
+}
+
+fn accumulator() -> gn_type!(i32, i32, Cmd) {
+    gn!(gen(cmd: Cmd) {
+        let mut cmd = cmd;
+        let mut total = 0;
+        loop {
+            match cmd {
+                Cmd::Add(v) => total += v,
+                Cmd::Reset => total = 0,
+                Cmd::Stop => return total,
+            }
+            cmd = yield total;
+        }
+    } -> i32, i32)
+}
+
+#[test]
+fn receives_arguments() {
+    let gn = accumulator();
+    let mut gn = pin!(gn);
+    assert_eq!(
+        gn.as_mut().resume_with(Cmd::Add(2)),
+        GeneratorState::Yielded(2)
+    );
+    assert_eq!(
+        gn.as_mut().resume_with(Cmd::Add(3)),
+        GeneratorState::Yielded(5)
+    );
+    assert_eq!(
+        gn.as_mut().resume_with(Cmd::Reset),
+        GeneratorState::Yielded(0)
+    );
+    assert_eq!(
+        gn.as_mut().resume_with(Cmd::Add(7)),
+        GeneratorState::Yielded(7)
+    );
+    assert_eq!(
+        gn.as_mut().resume_with(Cmd::Stop),
+        GeneratorState::Complete(7)
+    );
+}
+
+#[test]
+fn first_argument_discarded() {
+    let gn = gn!(gen(u8) {
+        let mut sum = 0u32;
+        for _ in 0..3 {
+            sum += u32::from(yield sum);
+        }
+        sum
+    } -> u32, u32);
+    let mut gn = pin!(gn);
+    assert_eq!(gn.as_mut().resume_with(100), GeneratorState::Yielded(0));
+    assert_eq!(gn.as_mut().resume_with(1), GeneratorState::Yielded(1));
+    assert_eq!(gn.as_mut().resume_with(2), GeneratorState::Yielded(3));
+    assert_eq!(gn.as_mut().resume_with(3), GeneratorState::Complete(6));
+}
+
+#[test]
+fn arguments_with_owned_data() {
+    let gn = gn!(gen(first: String) {
+        let mut line = first;
+        while !line.is_empty() {
+            line = yield line.len();
+        }
+    } -> usize);
+    let mut gn = pin!(gn);
+    assert_eq!(
+        gn.as_mut().resume_with(String::from("abc")),
+        GeneratorState::Yielded(3)
+    );
+    assert_eq!(
+        gn.as_mut().resume_with(String::from("hello")),
+        GeneratorState::Yielded(5)
+    );
+    assert_eq!(
+        gn.as_mut().resume_with(String::new()),
+        GeneratorState::Complete(())
+    );
+}
+
+#[generator(yield = usize, resume(word: &'static str))]
+fn lengths() {
+    let mut word = word;
+    loop {
+        word = r#yield!(word.len());
+    }
+}
+
+#[test]
+fn attribute_arguments() {
+    let gn = lengths();
+    let mut gn = pin!(gn);
+    assert_eq!(gn.as_mut().resume_with("a"), GeneratorState::Yielded(1));
+    assert_eq!(gn.as_mut().resume_with("four"), GeneratorState::Yielded(4));
+}
@@ -0,0 +1,33 @@
+#![cfg_attr(feature = "nightly", feature(coroutines))]
+
+use core::pin::pin;
+
+#[test]
+#[should_panic = "generator's future is pending, but nothing was yielded"]
+fn pending_without_yield() {
+    // not generated by `gn!`, but must not read an uninitialized item
+    let gn = yaag::sync::Gn::<_, String>::___new(|_| core::future::pending::<()>(), ());
+    let mut gn = pin!(gn);
+    let _ = gn.next();
+}
+
+#[cfg(feature = "async")]
+#[test]
+#[should_panic = "async generator's future is pending, but it neither yielded nor awaited"]
+fn async_pending_without_yield() {
+    use core::task::{Context, Waker};
+
+    let gn = yaag::not_sync::Gn::<_, String>::___new(|_| core::future::pending::<()>(), ());
+    let mut gn = pin!(gn);
+    let _ = gn
+        .as_mut()
+        .poll_next(&mut Context::from_waker(Waker::noop()));
+}
+
+#[test]
+fn generated_still_works() {
+    let gn = yaag::gn!(gen {
+        yield String::from("a");
+    } -> String);
+    assert_eq!(pin!(gn).collect::<Vec<_>>(), vec!["a"]);
+}
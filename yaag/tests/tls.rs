@@ -0,0 +1,55 @@
+#![cfg(feature = "tls")]
+#![cfg_attr(feature = "nightly", feature(coroutines))]
+
+use core::pin::pin;
+use yaag::gn;
+
+#[test]
+fn nested_generators() {
+    let gn = gn!(gen {
+        for i in 0..2 {
+            let inner = gn!(move gen {
+                yield i * 10;
+                yield i * 10 + 1;
+            } -> i32);
+            for item in pin!(inner) {
+                yield item;
+            }
+        }
+    } -> i32);
+    assert_eq!(pin!(gn).collect::<Vec<_>>(), vec![0, 1, 10, 11]);
+}
+
+#[test]
+fn survives_inner_panic() {
+    let gn = gn!(gen {
+        let inner = std::panic::catch_unwind(|| {
+            let inner = gn!(gen {
+                yield 1;
+                panic!("inner");
+            } -> i32);
+            pin!(inner).collect::<Vec<_>>()
+        });
+        yield inner.is_err();
+        yield true;
+    } -> bool);
+    assert_eq!(pin!(gn).collect::<Vec<_>>(), vec![true, true]);
+}
+
+#[cfg(feature = "async")]
+#[test]
+fn awaits_with_callers_waker() {
+    use core::{
+        future::poll_fn,
+        task::{Context, Poll, Waker},
+    };
+
+    let gn = gn!(async gen {
+        let same = poll_fn(|cx| Poll::Ready(cx.waker().will_wake(Waker::noop()))).await;
+        yield same;
+    } -> bool);
+    let mut gn = pin!(gn);
+    let mut cx = Context::from_waker(Waker::noop());
+    assert_eq!(gn.as_mut().poll_next(&mut cx), Poll::Ready(Some(true)));
+    assert_eq!(gn.as_mut().poll_next(&mut cx), Poll::Ready(None));
+}
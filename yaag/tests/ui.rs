@@ -1,5 +1,5 @@
 #[test]
-#[cfg(not(miri))]
+#[cfg(not(any(miri, feature = "nightly")))]
 fn ui() {
     let t = trybuild::TestCases::new();
     t.compile_fail("tests/ui/*.rs");
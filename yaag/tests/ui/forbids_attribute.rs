@@ -0,0 +1,10 @@
+use yaag::gn;
+
+fn main() {
+    gn! {
+        gen {
+            #[cfg_attr(all(), inline)]
+            yield 1;
+        } -> i32
+    };
+}
@@ -0,0 +1,12 @@
+use core::{future::pending, marker::PhantomData, pin::pin};
+
+fn main() {
+    let gn = yaag::sync::Gn::<_, String> {
+        fut: Some(pending::<()>()),
+        poisoned: false,
+        hint: (),
+        _ph: PhantomData,
+    };
+    let mut gn = pin!(gn);
+    let _ = gn.next();
+}
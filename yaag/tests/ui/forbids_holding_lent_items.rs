@@ -0,0 +1,14 @@
+use core::pin::pin;
+use yaag::{LendingIterator, gn};
+
+fn main() {
+    let gn = gn!(lending gen {
+        let mut buf = [0u8; 2];
+        yield &mut buf[..];
+        yield &mut buf[..];
+    } -> &'_ mut [u8]);
+    let mut gn = pin!(gn);
+    let first = gn.next();
+    let second = gn.next();
+    drop((first, second));
+}
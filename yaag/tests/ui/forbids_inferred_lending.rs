@@ -0,0 +1,7 @@
+use yaag::gn;
+
+fn main() {
+    let _ = gn!(lending gen {
+        yield &1;
+    } -> _);
+}
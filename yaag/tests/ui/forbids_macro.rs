@@ -1,10 +1,17 @@
 use yaag::gn;
 
+#[allow(unused_macros)]
+macro_rules! double {
+    ($e:expr) => {
+        $e * 2
+    };
+}
+
 fn main() {
     gn! {
         gen {
             let int = 42;
-            let _int = core::pin::pin!(int);
+            let _int = double!(int);
         } -> i32
     };
 }
@@ -0,0 +1,10 @@
+use core::pin::pin;
+use yaag::gn;
+
+fn main() {
+    let gn = gn!(gen {
+        yield 1u8;
+        yield "a";
+    });
+    for _ in pin!(gn) {}
+}
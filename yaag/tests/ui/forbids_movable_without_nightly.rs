@@ -0,0 +1,7 @@
+use yaag::gn;
+
+fn main() {
+    let _ = gn!(movable gen {
+        yield 1;
+    } -> i32);
+}
@@ -0,0 +1,9 @@
+use yaag::gn;
+
+fn main() {
+    gn! {
+        try gen {
+            yield 1;
+        } -> Result<i32, ()>, usize
+    };
+}
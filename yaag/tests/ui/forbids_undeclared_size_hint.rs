@@ -0,0 +1,8 @@
+use yaag::gn;
+
+fn main() {
+    let _ = gn!(gen {
+        size_hint!(1);
+        yield 1;
+    } -> i32);
+}
@@ -0,0 +1,10 @@
+use yaag::gn;
+
+fn main() {
+    gn! {
+        gen {
+            let int = 42;
+            assert!(int == 42; "not an expression list");
+        } -> i32
+    };
+}
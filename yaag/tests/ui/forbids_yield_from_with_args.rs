@@ -0,0 +1,9 @@
+use yaag::gn;
+
+fn main() {
+    gn! {
+        gen(i32) {
+            yield from [1, 2, 3];
+        } -> i32
+    };
+}
@@ -0,0 +1,12 @@
+use yaag::gn;
+
+fn main() {
+    gn! {
+        gen {
+            let fut = async {
+                yield 1;
+            };
+            drop(fut);
+        } -> i32
+    };
+}
@@ -0,0 +1,9 @@
+use yaag::gn;
+
+fn main() {
+    gn! {
+        gen {
+            [1, 2, 3].iter().for_each(|item| yield *item);
+        } -> i32
+    };
+}
@@ -0,0 +1,11 @@
+use yaag::gn;
+
+fn main() {
+    gn! {
+        gen {
+            fn helper() {
+                r#yield!(1);
+            }
+        } -> i32
+    };
+}
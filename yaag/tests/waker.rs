@@ -0,0 +1,144 @@
+#![forbid(unsafe_code)]
+
+use core::{
+    pin::pin,
+    task::{Context, Waker},
+};
+
+/// Token smuggled out of the generator, which still must not be usable outside of it
+fn sync_token<O>() -> yaag::sync::Token<O, ()> {
+    let mut token = None;
+    let _ = yaag::sync::Gn::<_, O>::___new(
+        |t| {
+            token = Some(t);
+            core::future::ready(())
+        },
+        (),
+    );
+    token.unwrap()
+}
+
+#[cfg(feature = "async")]
+fn async_token<O>() -> yaag::not_sync::Token<O> {
+    let mut token = None;
+    let _ = yaag::not_sync::Gn::<_, O>::___new(
+        |t| {
+            token = Some(t);
+            core::future::ready(())
+        },
+        (),
+    );
+    token.unwrap()
+}
+
+#[test]
+#[should_panic(expected = "must only be awaited inside of the generator code")]
+fn yield_rejects_foreign_waker() {
+    let yld = pin!(yaag::sync::Yield::___make(&sync_token(), 42));
+    let _ = yld.poll(&mut Context::from_waker(Waker::noop()));
+}
+
+#[cfg(feature = "async")]
+#[test]
+#[should_panic(expected = "must only be awaited inside of the generator code")]
+fn async_yield_rejects_foreign_waker() {
+    let yld = pin!(yaag::not_sync::Yield::___make(&async_token(), 42));
+    let _ = yld.poll(&mut Context::from_waker(Waker::noop()));
+}
+
+#[cfg(feature = "async")]
+#[test]
+#[should_panic(expected = "must only be awaited inside of the generator code")]
+fn await_rejects_foreign_waker() {
+    let awt = pin!(yaag::not_sync::Await::___make(
+        &async_token::<i32>(),
+        core::future::ready(())
+    ));
+    let _ = awt.poll(&mut Context::from_waker(Waker::noop()));
+}
+
+#[test]
+#[should_panic(expected = "must only be awaited inside of the generator code")]
+fn yield_rejects_waker_outliving_the_poll() {
+    use core::{cell::RefCell, task::Poll};
+
+    let waker = RefCell::new(None);
+    let token = RefCell::new(None);
+    let gn = yaag::sync::Gn::<_, u64>::___new(
+        |t| {
+            *token.borrow_mut() = Some(t);
+            core::future::poll_fn(|cx| {
+                *waker.borrow_mut() = Some(cx.waker().clone());
+                Poll::Ready(())
+            })
+        },
+        (),
+    );
+    let _ = pin!(gn).resume();
+    // generator's state is gone by now, so the clone must not lead to it
+    let token = token.borrow_mut().take().unwrap();
+    let waker = waker.borrow_mut().take().unwrap();
+    let yld = pin!(yaag::sync::Yield::___make(&token, 7u64));
+    let _ = yld.poll(&mut Context::from_waker(&waker));
+}
+
+#[test]
+#[should_panic(expected = "polled by another generator")]
+fn yield_rejects_another_generators_token() {
+    let token = sync_token::<String>();
+    // `Closable` is the async block backend, even with `nightly` feature
+    let gn = yaag::sync::Gn::<_, u8>::___new(
+        move |_| {
+            yaag::sync::Closable::___new(async move {
+                yaag::sync::Yield::___make(&token, String::from("smuggled")).await;
+            })
+        },
+        (),
+    );
+    let _ = pin!(gn).resume();
+}
+
+#[test]
+#[should_panic(expected = "polled by another generator")]
+fn lend_yield_rejects_another_generators_token() {
+    use yaag::LendingIterator;
+
+    let mut token = None;
+    let _ = yaag::sync::LendingGn::<_, yaag::Ref<str>>::___new(|t| {
+        token = Some(t);
+        core::future::ready(())
+    });
+    let token = token.unwrap();
+    let gn = yaag::sync::LendingGn::<_, yaag::Ref<u8>>::___new(move |_| async move {
+        yaag::sync::LendYield::___make(&token, "smuggled").await;
+    });
+    let _ = pin!(gn).next();
+}
+
+#[cfg(feature = "async")]
+#[test]
+#[should_panic(expected = "polled by another generator")]
+fn async_yield_rejects_another_generators_token() {
+    let token = async_token::<String>();
+    let gn = yaag::not_sync::Gn::<_, u8>::___new(
+        move |_| async move {
+            yaag::not_sync::Yield::___make(&token, String::from("smuggled")).await;
+        },
+        (),
+    );
+    let _ = pin!(gn).poll_next(&mut Context::from_waker(Waker::noop()));
+}
+
+#[cfg(feature = "async")]
+#[test]
+#[should_panic(expected = "polled by another generator")]
+fn await_rejects_another_generators_token() {
+    let token = async_token::<String>();
+    let gn = yaag::not_sync::Gn::<_, u8>::___new(
+        move |_| async move {
+            yaag::not_sync::Await::___make(&token, core::future::pending::<()>()).await;
+        },
+        (),
+    );
+    let _ = pin!(gn).poll_next(&mut Context::from_waker(Waker::noop()));
+}
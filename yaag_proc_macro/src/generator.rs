@@ -0,0 +1,135 @@
+use crate::gn::{self, AllowMacros, Hint, ResumeArg};
+use syn::{
+    Ident, ItemFn, Path, ReturnType, Token, Type, parse::Parse, parse_quote, spanned::Spanned,
+};
+
+pub struct Args {
+    out: Type,
+    arg: Option<ResumeArg>,
+    macros: Vec<Path>,
+    hint: Option<Hint>,
+    is_try: bool,
+    is_closable: bool,
+    movable: Option<gn::kw::movable>,
+}
+
+impl Parse for Args {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let _ = input.parse::<Token![yield]>()?;
+        let _ = input.parse::<Token![=]>()?;
+        let out = input.parse::<Type>()?;
+        let mut arg = None;
+        let mut macros = Vec::new();
+        let mut hint = None;
+        let mut is_try = false;
+        let mut is_closable = false;
+        let mut movable = None;
+        while input.parse::<Option<Token![,]>>()?.is_some() && !input.is_empty() {
+            if input.peek(gn::kw::allow_macros) {
+                macros.extend(input.parse::<AllowMacros>()?.0);
+                continue;
+            }
+            if Hint::peek(input) && hint.is_none() {
+                hint = Some(input.parse::<Hint>()?);
+                continue;
+            }
+            if !is_try && input.parse::<Option<Token![try]>>()?.is_some() {
+                is_try = true;
+                continue;
+            }
+            if !is_closable && input.parse::<Option<gn::kw::closable>>()?.is_some() {
+                is_closable = true;
+                continue;
+            }
+            if movable.is_none() && input.peek(gn::kw::movable) {
+                movable = Some(input.parse::<gn::kw::movable>()?);
+                continue;
+            }
+            let key = input.parse::<Ident>()?;
+            if key == "resume" && arg.is_none() {
+                arg = Some(input.parse::<ResumeArg>()?);
+            } else {
+                return Err(syn::Error::new_spanned(key, "Unexpected argument"));
+            }
+        }
+        if !input.is_empty() {
+            return Err(input.error("Extra input"));
+        }
+        Ok(Self {
+            out,
+            arg,
+            macros,
+            hint,
+            is_try,
+            is_closable,
+            movable,
+        })
+    }
+}
+
+impl Args {
+    pub fn expand(self, mut item: ItemFn) -> syn::Result<ItemFn> {
+        let is_async = item.sig.asyncness.take().is_some();
+        let out = self.out;
+        // declared return type becomes generator's completion value
+        let ret = match &item.sig.output {
+            ReturnType::Default => None,
+            ReturnType::Type(_, ty) if self.is_try => {
+                return Err(syn::Error::new(
+                    ty.span(),
+                    "Completion values are not supported by try generators",
+                ));
+            }
+            ReturnType::Type(_, ty) => Some(Type::clone(ty)),
+        };
+        if let Some(movable) = &self.movable {
+            gn::check_movable(
+                movable,
+                is_async,
+                false,
+                self.is_closable,
+                self.hint.is_some(),
+            )?;
+        }
+        let hint = Hint::ty(self.hint.as_ref());
+        let ret_ty = ret.clone().unwrap_or_else(|| parse_quote!(()));
+        item.sig.output = if is_async {
+            if let Some(arg) = &self.arg {
+                return Err(syn::Error::new(
+                    arg.ty.span(),
+                    "Resume arguments are not supported by async generators",
+                ));
+            }
+            parse_quote! {
+                -> ::yaag::not_sync::Gn<impl ::core::future::Future<Output = #ret_ty>, #out, #ret_ty, #hint>
+            }
+        } else {
+            let ret = ret_ty;
+            let arg = self
+                .arg
+                .as_ref()
+                .map_or_else(|| parse_quote!(()), |arg| arg.ty.clone());
+            parse_quote! {
+                -> ::yaag::sync::Gn<impl ::yaag::sync::Raw<#out, #ret, #arg>, #out, #ret, #arg, #hint>
+            }
+        };
+        let input = gn::Input {
+            is_async,
+            // arguments are owned by the function, so they must be moved into the generator
+            is_move: true,
+            is_try: self.is_try,
+            is_lending: false,
+            is_closable: self.is_closable,
+            movable: self.movable,
+            code: *item.block,
+            out,
+            ret,
+            arg: self.arg,
+            macros: self.macros,
+            hint: self.hint,
+        };
+        let expr = input.expand();
+        item.block = parse_quote!({ #expr });
+        Ok(item)
+    }
+}
@@ -1,18 +1,168 @@
-use crate::trans::Trans;
-use syn::{Block, Expr, Ident, Token, Type, parse::Parse, parse_quote};
+use crate::{
+    lending,
+    trans::{Ctx, HintKind, Trans, rewrite_yield_from, token},
+};
+use proc_macro2::{Group, Span, TokenStream, TokenTree};
+use quote::{ToTokens, quote};
+use syn::{
+    Block, Expr, Ident, Pat, Path, Token, Type, parenthesized, parse::Parse, parse_quote,
+    parse_quote_spanned, punctuated::Punctuated, spanned::Spanned,
+};
 
-pub struct Input {
+/// Resume argument declaration, either `(pat: Type)` or just `(Type)`
+pub struct ResumeArg {
+    pub pat: Pat,
+    pub ty: Type,
+}
+
+impl Parse for ResumeArg {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let content;
+        parenthesized!(content in input);
+        let fork = content.fork();
+        let pat = if Pat::parse_single(&fork).is_ok() && fork.peek(Token![:]) {
+            let pat = Pat::parse_single(&content)?;
+            let _ = content.parse::<Token![:]>()?;
+            pat
+        } else {
+            parse_quote!(_)
+        };
+        let ty = content.parse::<Type>()?;
+        if !content.is_empty() {
+            return Err(content.error("Extra input"));
+        }
+        Ok(Self { pat, ty })
+    }
+}
+
+pub mod kw {
+    syn::custom_keyword!(allow_macros);
+    syn::custom_keyword!(closable);
+    syn::custom_keyword!(lending);
+    syn::custom_keyword!(len);
+    syn::custom_keyword!(movable);
+    syn::custom_keyword!(size_hint);
+}
+
+/// Declared number of items: either exact `len = n`, or `size_hint = (lo, hi)`
+pub enum Hint {
+    Len(Expr),
+    SizeHint(Expr),
+}
+
+impl Hint {
+    /// Whether the input starts with a hint, rather than a type
+    pub fn peek(input: syn::parse::ParseStream) -> bool {
+        (input.peek(kw::len) || input.peek(kw::size_hint)) && input.peek2(Token![=])
+    }
+
+    pub fn kind(&self) -> HintKind {
+        match self {
+            Self::Len(_) => HintKind::Len,
+            Self::SizeHint(_) => HintKind::SizeHint,
+        }
+    }
+
+    pub fn ty(hint: Option<&Self>) -> Type {
+        match hint {
+            None => parse_quote!(()),
+            Some(Self::Len(_)) => parse_quote!(::yaag::Len),
+            Some(Self::SizeHint(_)) => parse_quote!(::yaag::SizeHint),
+        }
+    }
+
+    pub fn expr(hint: Option<&Self>) -> Expr {
+        match hint {
+            None => parse_quote!(()),
+            Some(Self::Len(len)) => parse_quote_spanned! { len.span() => ::yaag::Len(#len) },
+            Some(Self::SizeHint(hint)) => parse_quote_spanned! { hint.span() => {
+                let (lo, hi): (usize, ::core::option::Option<usize>) = #hint;
+                ::yaag::SizeHint(lo, hi)
+            }},
+        }
+    }
+}
+
+impl Parse for Hint {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        if input.parse::<Option<kw::len>>()?.is_some() {
+            let _ = input.parse::<Token![=]>()?;
+            Ok(Self::Len(input.parse()?))
+        } else {
+            let _ = input.parse::<kw::size_hint>()?;
+            let _ = input.parse::<Token![=]>()?;
+            Ok(Self::SizeHint(input.parse()?))
+        }
+    }
+}
+
+/// User macros allowed inside of the generator: `allow_macros(path, ..)`
+pub struct AllowMacros(pub Vec<Path>);
+
+impl Parse for AllowMacros {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let _ = input.parse::<kw::allow_macros>()?;
+        let content;
+        parenthesized!(content in input);
+        let paths =
+            Punctuated::<Path, Token![,]>::parse_terminated_with(&content, Path::parse_mod_style)?;
+        Ok(Self(paths.into_iter().collect()))
+    }
+}
+
+/// Checks `movable` generator, that's a movable native coroutine rather than a `static` one
+pub fn check_movable(
+    movable: &kw::movable,
     is_async: bool,
-    is_move: bool,
-    code: Block,
-    out: Type,
+    is_lending: bool,
+    is_closable: bool,
+    has_hint: bool,
+) -> syn::Result<()> {
+    if !cfg!(feature = "nightly") {
+        return Err(syn::Error::new_spanned(
+            movable,
+            "Movable generators need `nightly` feature of yaag, as these are native coroutines",
+        ));
+    }
+    if is_async || is_lending || is_closable || has_hint {
+        return Err(syn::Error::new_spanned(
+            movable,
+            "Movable generators can't be async, lending, closable or have size hints, as these aren't native coroutines",
+        ));
+    }
+    Ok(())
+}
+
+pub struct Input {
+    pub is_async: bool,
+    pub is_move: bool,
+    pub is_try: bool,
+    pub is_lending: bool,
+    pub is_closable: bool,
+    pub movable: Option<kw::movable>,
+    pub code: Block,
+    pub out: Type,
+    pub ret: Option<Type>,
+    pub arg: Option<ResumeArg>,
+    pub macros: Vec<Path>,
+    pub hint: Option<Hint>,
 }
 
 impl Parse for Input {
     fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
         let mut is_async = false;
         let mut is_move = false;
-        while !input.peek(Ident) {
+        let mut is_try = false;
+        let mut is_lending = false;
+        let mut is_closable = false;
+        let mut movable = None;
+        let mut macros = Vec::new();
+        while !input.peek(Ident)
+            || input.peek(kw::allow_macros)
+            || input.peek(kw::lending)
+            || input.peek(kw::closable)
+            || input.peek(kw::movable)
+        {
             if input.parse::<Token![async]>().is_ok() {
                 is_async = true;
                 continue;
@@ -21,23 +171,111 @@ impl Parse for Input {
                 is_move = true;
                 continue;
             }
-            return Err(input.error("Expected move or async"));
+            if input.parse::<Token![try]>().is_ok() {
+                is_try = true;
+                continue;
+            }
+            if input.parse::<Option<kw::lending>>()?.is_some() {
+                is_lending = true;
+                continue;
+            }
+            if input.parse::<Option<kw::closable>>()?.is_some() {
+                is_closable = true;
+                continue;
+            }
+            if movable.is_none() && input.peek(kw::movable) {
+                movable = Some(input.parse::<kw::movable>()?);
+                continue;
+            }
+            if input.peek(kw::allow_macros) {
+                macros.extend(input.parse::<AllowMacros>()?.0);
+                continue;
+            }
+            return Err(input
+                .error("Expected move, async, try, lending, closable, movable or allow_macros"));
         }
         let r#gen = input.parse::<Ident>().unwrap();
         if r#gen != "gen" {
             return Err(syn::Error::new_spanned(r#gen, "Expected `gen` keyword"));
         }
-        let code = input.parse::<Block>()?;
-        let _ = input.parse::<Token![->]>()?;
-        let out = input.parse::<Type>()?;
+        let arg = if input.peek(syn::token::Paren) {
+            let arg = input.parse::<ResumeArg>()?;
+            if is_async {
+                return Err(syn::Error::new(
+                    arg.ty.span(),
+                    "Resume arguments are not supported by async generators",
+                ));
+            }
+            Some(arg)
+        } else {
+            None
+        };
+        let code = input.parse::<Group>()?;
+        let code = TokenTree::Group(code).into();
+        let code = syn::parse2::<Block>(rewrite_yield_from(code)?)?;
+        // item type is inferred from the yields, when it's omitted or `_`
+        let out = if input.is_empty() {
+            parse_quote!(_)
+        } else {
+            let _ = input.parse::<Token![->]>()?;
+            input.parse::<Type>()?
+        };
+        let mut ret = None;
+        let mut hint = None;
+        while input.parse::<Option<Token![,]>>()?.is_some() && !input.is_empty() {
+            if Hint::peek(input) && hint.is_none() {
+                hint = Some(input.parse::<Hint>()?);
+                continue;
+            }
+            if ret.is_some() || hint.is_some() {
+                return Err(input.error("Extra input"));
+            }
+            let ty = input.parse::<Type>()?;
+            if is_try {
+                return Err(syn::Error::new_spanned(
+                    ty,
+                    "Completion values are not supported by try generators",
+                ));
+            }
+            ret = Some(ty);
+        }
         if !input.is_empty() {
             return Err(input.error("Extra input"));
         }
+        if is_lending && matches!(out, Type::Infer(_)) {
+            return Err(syn::Error::new(
+                out.span(),
+                "Lending generators need an explicit item type, like `-> &'_ T`",
+            ));
+        }
+        if is_lending && is_closable {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                "Lending generators can't be closable",
+            ));
+        }
+        if is_lending && (is_async || is_try || arg.is_some() || ret.is_some() || hint.is_some()) {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                "Lending generators can't be async or fallible, and have neither resume arguments, completion values, nor size hints",
+            ));
+        }
+        if let Some(movable) = &movable {
+            check_movable(movable, is_async, is_lending, is_closable, hint.is_some())?;
+        }
         Ok(Self {
             is_async,
             is_move,
+            is_try,
+            is_lending,
+            is_closable,
+            movable,
             code,
             out,
+            ret,
+            arg,
+            macros,
+            hint,
         })
     }
 }
@@ -45,21 +283,120 @@ impl Parse for Input {
 impl Input {
     pub fn expand(mut self) -> Expr {
         let mv: Option<Token![move]> = self.is_move.then(Default::default);
-        let out = self.out;
-        self.code.trans(&out, self.is_async);
-        let code = self.code;
-        let module: Ident = if self.is_async {
-            parse_quote!(not_sync)
+        let (family_items, family) = if self.is_lending {
+            let (items, family) = lending::family(&self.out);
+            (items, Some(family))
         } else {
-            parse_quote!(sync)
+            (TokenStream::new(), None)
         };
-        parse_quote! {{
-            #[allow(unused_unsafe)]
-            let fut = async #mv #code;
-            ::yaag::#module::Gn {
-                fut,
-                _ph: ::core::marker::PhantomData::<#out>,
+        let ctx = Ctx {
+            // lending generators rely on the waker trick, to lend borrows of their own state,
+            // and native `yield` can't be told that the generator is closed, nor hand it `size_hint!`s
+            native: cfg!(feature = "nightly")
+                && !self.is_async
+                && !self.is_closable
+                && self.hint.is_none()
+                && family.is_none(),
+            lending: family,
+            arg: self.arg.as_ref().map(|arg| arg.ty.clone()),
+            is_async: self.is_async,
+            is_try: self.is_try,
+            is_closable: self.is_closable,
+            in_try_block: false,
+            hint: self.hint.as_ref().map(Hint::kind),
+            macros: self
+                .macros
+                .iter()
+                .map(|path| path.to_token_stream().to_string())
+                .collect(),
+        };
+        self.code.trans(&ctx);
+        let mut code = self.code;
+        if ctx.native {
+            // movable coroutine can't hold borrows across yields, but it's `Unpin`, and `Clone` with `coroutine_clone`
+            let st: Option<Token![static]> = self.movable.is_none().then(Default::default);
+            let kind = quote!(#st #mv);
+            return self::native(&ctx, code, kind, self.out, self.arg, self.ret, self.hint);
+        }
+        if let Some(ResumeArg { pat, ty }) = &self.arg {
+            // first resume argument is received before running any of the generator's code
+            let first = ctx.first_arg_expr();
+            code = parse_quote!({
+                let #pat: #ty = #first;
+                #code
+            });
+        }
+        let token = token();
+        // token is moved into the future, even if it borrows everything else
+        let mut body: Expr = parse_quote! {
+            async #mv {
+                #[allow(unused_variables)]
+                let #token = #token;
+                #code
             }
+        };
+        if self.is_closable && !ctx.is_async {
+            body = parse_quote!(::yaag::sync::Closable::___new(#body));
+        }
+        let fut: Expr = parse_quote!(|#token| #body);
+        if let Some(family) = &ctx.lending {
+            return parse_quote! {{
+                #family_items
+                ::yaag::sync::LendingGn::<_, #family>::___new(#fut)
+            }};
+        }
+        let module = ctx.module();
+        // not visible to the generator code
+        let hint_var = Ident::new("hint", Span::mixed_site());
+        let out = &self.out;
+        let hint_ty = Hint::ty(self.hint.as_ref());
+        let hint = Hint::expr(self.hint.as_ref());
+        let ret = self.ret.unwrap_or_else(|| parse_quote!(()));
+        let generics = if ctx.is_async {
+            // async generators have no resume arguments (yet)
+            quote!(_, #out, #ret, #hint_ty)
+        } else {
+            let arg = ctx.arg_ty();
+            quote!(_, #out, #ret, #arg, #hint_ty)
+        };
+        // async generator can't tell it's closable from the future type
+        let new = if self.is_closable && ctx.is_async {
+            quote!(___new_closable)
+        } else {
+            quote!(___new)
+        };
+        parse_quote! {{
+            // hint is evaluated before any of the values are moved into the generator
+            let #hint_var = #hint;
+            ::yaag::#module::Gn::<#generics>::#new(#fut, #hint_var)
         }}
     }
 }
+
+/// Synchronous generator as a native coroutine
+fn native(
+    ctx: &Ctx,
+    code: Block,
+    kind: TokenStream,
+    out: Type,
+    arg: Option<ResumeArg>,
+    ret: Option<Type>,
+    hint: Option<Hint>,
+) -> Expr {
+    let hint_var = Ident::new("hint", Span::mixed_site());
+    let ret = ret.unwrap_or_else(|| parse_quote!(()));
+    let arg_ty = ctx.arg_ty();
+    let hint_ty = Hint::ty(hint.as_ref());
+    let hint = Hint::expr(hint.as_ref());
+    // first resume argument is the closure's argument, just like in the `async` version
+    let params = arg.map(|ResumeArg { pat, ty }| quote!(#pat: #ty));
+    parse_quote! {{
+        // hint is evaluated before any of the values are moved into the generator
+        let #hint_var = #hint;
+        ::yaag::sync::Gn::<_, #out, #ret, #arg_ty, #hint_ty>::___new(
+            // attributes are allowed on the call arguments without `stmt_expr_attributes`
+            |_| ::yaag::sync::Native::___new(#[coroutine] #kind |#params| -> #ret #code),
+            #hint_var,
+        )
+    }}
+}
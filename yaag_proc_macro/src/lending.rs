@@ -0,0 +1,58 @@
+use proc_macro2::{Group, Span, TokenStream, TokenTree};
+use quote::{ToTokens, quote};
+use syn::{Lifetime, Type, TypeReference, parse_quote};
+
+/// Replaces all of the `'_` lifetimes with `lt`
+fn replace_elided(tokens: TokenStream, lt: &Lifetime) -> TokenStream {
+    let mut tokens = tokens.into_iter().peekable();
+    let mut output = TokenStream::new();
+    while let Some(token) = tokens.next() {
+        match token {
+            TokenTree::Punct(punct)
+                if punct.as_char() == '\''
+                    && matches!(tokens.peek(), Some(TokenTree::Ident(ident)) if ident == "_") =>
+            {
+                let _ = tokens.next();
+                output.extend(lt.to_token_stream());
+            }
+            TokenTree::Group(group) => {
+                let mut new = Group::new(group.delimiter(), replace_elided(group.stream(), lt));
+                new.set_span(group.span());
+                output.extend([TokenTree::Group(new)]);
+            }
+            token => output.extend([token]),
+        }
+    }
+    output
+}
+
+/// Type family for the lent type, with the items that define it (if any).
+///
+/// Plain references are covered by `Ref` and `Mut` families, so these may use the generic parameters.
+/// Anything else gets a local family, with each `'_` being the borrow.
+pub fn family(out: &Type) -> (TokenStream, Type) {
+    if let Type::Reference(TypeReference {
+        lifetime,
+        mutability,
+        elem,
+        ..
+    }) = out
+        && lifetime.as_ref().is_none_or(|lt| lt.ident == "_")
+    {
+        return if mutability.is_some() {
+            (TokenStream::new(), parse_quote!(::yaag::Mut<#elem>))
+        } else {
+            (TokenStream::new(), parse_quote!(::yaag::Ref<#elem>))
+        };
+    }
+    let family = syn::Ident::new("Family", Span::mixed_site());
+    let lt = Lifetime::new("'lend", Span::mixed_site());
+    let item = replace_elided(out.to_token_stream(), &lt);
+    let items = quote! {
+        struct #family;
+        impl ::yaag::Lend for #family {
+            type Item<#lt> = #item;
+        }
+    };
+    (items, parse_quote!(#family))
+}
@@ -1,13 +1,27 @@
 use proc_macro::TokenStream;
 use quote::ToTokens;
-use syn::parse_macro_input;
+use syn::{ItemFn, parse_macro_input};
 
 mod trans;
 
 mod gn;
 
+mod generator;
+
+mod lending;
+
 #[proc_macro]
 pub fn gn(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as gn::Input);
     input.expand().into_token_stream().into()
 }
+
+#[proc_macro_attribute]
+pub fn generator(args: TokenStream, item: TokenStream) -> TokenStream {
+    let args = parse_macro_input!(args as generator::Args);
+    let item = parse_macro_input!(item as ItemFn);
+    match args.expand(item) {
+        Ok(item) => item.into_token_stream().into(),
+        Err(err) => err.into_compile_error().into(),
+    }
+}
@@ -1,5 +1,11 @@
+use proc_macro2::{Delimiter, Group, Span, TokenStream, TokenTree};
+use quote::{ToTokens, quote, quote_spanned};
 use syn::{
-    Arm, Block, Expr, FieldValue, Ident, Item, Local, Stmt, Type, parse_quote, parse_quote_spanned,
+    Arm, Attribute, Block, Expr, FieldValue, Ident, Item, Local, Macro, Meta, Path, Stmt, Token,
+    Type,
+    parse::{Parse, ParseStream, Parser},
+    parse_quote, parse_quote_spanned,
+    punctuated::Punctuated,
     spanned::Spanned,
 };
 
@@ -8,68 +14,656 @@ Macro invocations are not allowed inside of the main generator code. Please do o
 - extract it into a function
 - extract it into a closure
 - move it out of the generator
+- if macro is known to expand without any await points, list it in `allow_macros(..)`
 ";
+const MACRO_ARGS_ERROR: &str = "
+Could not parse macro arguments as expressions.
+";
+
+/// Standard macros, that are known to expand without any await points.
+///
+/// Their arguments are transformed as ordinary expressions.
+const STD_MACROS: &[&str] = &[
+    "assert",
+    "assert_eq",
+    "assert_ne",
+    "dbg",
+    "debug_assert",
+    "debug_assert_eq",
+    "debug_assert_ne",
+    "eprint",
+    "eprintln",
+    "format",
+    "format_args",
+    "matches",
+    "panic",
+    "pin",
+    "print",
+    "println",
+    "todo",
+    "unimplemented",
+    "unreachable",
+    "vec",
+    "write",
+    "writeln",
+];
 const AWAIT_ERROR: &str = "
 Await outside of async context.
 ";
+const YIELD_FROM_ARG_ERROR: &str = "
+`yield from` is not supported inside of the generators with resume arguments.
+";
+const SIZE_HINT_UNDECLARED_ERROR: &str = "
+`size_hint!` needs a `len` or `size_hint` declared in the generator header.
+";
 const ATTR_ERROR: &str = "
 Attributes are FORBIDDEN inside of the generator code, as they can expand into unintended code.
+Only `cfg`, `cfg_attr` (of allowed attributes), lint attributes (`allow`, `expect`, `warn`, `deny`, `forbid`) and `rustfmt::skip` are allowed.
 ";
 
+const NESTED_YIELD_ERROR: &str = "
+`yield` can't be used inside of closures, async blocks and nested items, as these are not a part of the generator. Please do one of the following:
+- move the `yield` out: return the value from the closure (block, function), and yield it from the generator itself
+- make the closure (block, function) a generator of its own with `gn!`, and `yield from` it
+";
+const YIELD_FROM_LENDING_ERROR: &str = "
+`yield from` is not supported by lending generators.
+";
+const UNSUPPORTED_ERROR: &str = "
+This expression is not supported inside of the generator code.
+";
+
+/// Attributes, that are known to never introduce any await points.
+const ALLOWED_ATTRS: &[&str] = &["allow", "cfg", "deny", "expect", "forbid", "warn"];
+
+fn is_allowed_attr_path(path: &Path) -> bool {
+    ALLOWED_ATTRS.iter().any(|attr| path.is_ident(attr))
+        || (path.segments.len() == 2
+            && path.leading_colon.is_none()
+            && path.segments[0].ident == "rustfmt"
+            && path.segments[1].ident == "skip")
+}
+
+fn is_allowed_attr(attr: &Attribute) -> bool {
+    let path = attr.path();
+    if is_allowed_attr_path(path) {
+        return true;
+    }
+    if !path.is_ident("cfg_attr") {
+        return false;
+    }
+    // `cfg_attr(predicate, attr, ..)`: all of the conditional attributes must be allowed too
+    attr.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)
+        .is_ok_and(|metas| {
+            metas.len() > 1
+                && metas
+                    .iter()
+                    .skip(1)
+                    .all(|meta| is_allowed_attr_path(meta.path()))
+        })
+}
+
+fn are_allowed_attrs(attrs: &[Attribute]) -> bool {
+    attrs.iter().all(is_allowed_attr)
+}
+
+/// `r#yield!(..)` is an alternative spelling of `yield ..`, for the places where `yield` keyword itself is rejected by the compiler (like inside of the attribute macro input).
+///
+/// Returns already transformed expression.
+fn yield_macro(mac: &Macro, ctx: &Ctx) -> Option<Expr> {
+    if !mac.path.is_ident("r#yield") {
+        return None;
+    }
+    let span = mac.path.span();
+    Some(match mac.parse_body::<YieldBody>() {
+        Ok(YieldBody::Value(mut expr)) => {
+            expr.trans(ctx);
+            ctx.yield_expr(&expr, span)
+        }
+        Ok(YieldBody::From(mut expr)) => {
+            expr.trans(ctx);
+            ctx.yield_from_expr(&expr, span)
+        }
+        Err(err) => {
+            let err = err.into_compile_error();
+            parse_quote!(#err)
+        }
+    })
+}
+
+/// `size_hint!(..)` re-declares the hint of the generator, with the same expression as its header does (after `len =` or `size_hint =`).
+///
+/// Returns already transformed expression.
+fn size_hint_macro(mac: &Macro, ctx: &Ctx) -> Option<Expr> {
+    if !mac.path.is_ident("size_hint") {
+        return None;
+    }
+    let span = mac.path.span();
+    Some(match mac.parse_body::<Expr>() {
+        Ok(mut expr) => {
+            expr.trans(ctx);
+            ctx.set_hint_expr(&expr, span)
+        }
+        Err(err) => {
+            let err = err.into_compile_error();
+            parse_quote!(#err)
+        }
+    })
+}
+
+/// Contents of the `r#yield!(..)`
+enum YieldBody {
+    /// `r#yield!(expr)`
+    Value(Expr),
+    /// `r#yield!(from expr)`
+    From(Expr),
+}
+
+impl Parse for YieldBody {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        if input.is_empty() {
+            return Ok(Self::Value(parse_quote!(())));
+        }
+        let fork = input.fork();
+        if fork.parse::<Ident>().is_ok_and(|from| from == "from") && !fork.is_empty() {
+            let _ = input.parse::<Ident>()?;
+            return Ok(Self::From(input.parse()?));
+        }
+        Ok(Self::Value(input.parse()?))
+    }
+}
+
+/// Whether attribute tokens (`[..]` group after `#`) are a `generator` attribute
+fn is_generator_attr(group: &Group) -> bool {
+    group
+        .stream()
+        .into_iter()
+        .take_while(|token| !matches!(token, TokenTree::Group(_)))
+        .filter_map(|token| match token {
+            TokenTree::Ident(ident) => Some(ident),
+            _ => None,
+        })
+        .last()
+        .is_some_and(|ident| ident == "generator")
+}
+
+/// Finds a `yield` (or `r#yield!`) in the code, that is out of the transform's reach.
+///
+/// Nested `gn!` invocations and `#[generator]` functions are generators of their own, so their `yield`s are fine.
+fn find_yield(tokens: TokenStream) -> Option<Span> {
+    let mut tokens = tokens.into_iter().peekable();
+    while let Some(token) = tokens.next() {
+        match token {
+            TokenTree::Ident(ident) if ident == "yield" || ident == "r#yield" => {
+                return Some(ident.span());
+            }
+            TokenTree::Ident(ident)
+                if ident == "gn"
+                    && matches!(tokens.peek(), Some(TokenTree::Punct(punct)) if punct.as_char() == '!') =>
+            {
+                // skip `!` and the macro input
+                let _ = tokens.next();
+                let _ = tokens.next();
+            }
+            TokenTree::Punct(punct) if punct.as_char() == '#' => {
+                if let Some(TokenTree::Group(attr)) = tokens.peek()
+                    && is_generator_attr(attr)
+                {
+                    // skip until the function body
+                    for token in tokens.by_ref() {
+                        if matches!(&token, TokenTree::Group(group) if group.delimiter() == Delimiter::Brace)
+                        {
+                            break;
+                        }
+                    }
+                }
+            }
+            TokenTree::Group(group) => {
+                if let Some(span) = find_yield(group.stream()) {
+                    return Some(span);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Whether `token` after `yield from` starts the delegated expression, rather than continuing an expression on a variable named `from`
+///
+/// Operators (`yield from - 1`), calls, indexing, method calls and casts are left to the variable,
+/// so delegation to these is spelled `r#yield!(from ..)`.
+fn starts_delegation(token: &TokenTree) -> bool {
+    match token {
+        TokenTree::Ident(ident) => ident != "as",
+        TokenTree::Literal(_) => true,
+        // label of the delegated loop or block
+        TokenTree::Punct(punct) => punct.as_char() == '\'',
+        TokenTree::Group(group) => match group.delimiter() {
+            Delimiter::Brace => true,
+            // array, as `from[a, b]` and `from[a; n]` are not indexing
+            Delimiter::Bracket => {
+                group.stream().is_empty()
+                    || group.stream().into_iter().any(|token| {
+                        matches!(&token, TokenTree::Punct(punct) if matches!(punct.as_char(), ',' | ';'))
+                    })
+            }
+            Delimiter::Parenthesis | Delimiter::None => false,
+        },
+    }
+}
+
+/// Replaces `yield from expr` (that is not a valid syntax) with `r#yield!(from expr)`
+pub fn rewrite_yield_from(input: TokenStream) -> syn::Result<TokenStream> {
+    let mut tokens = input.into_iter();
+    let mut output = TokenStream::new();
+    while let Some(token) = tokens.next() {
+        match token {
+            TokenTree::Group(group) => {
+                let mut new = Group::new(group.delimiter(), rewrite_yield_from(group.stream())?);
+                new.set_span(group.span());
+                output.extend([TokenTree::Group(new)]);
+            }
+            TokenTree::Ident(yld) if yld == "yield" => {
+                let mut lookahead = tokens.clone();
+                if let (Some(TokenTree::Ident(from)), Some(next)) =
+                    (lookahead.next(), lookahead.next())
+                    && from == "from"
+                    && starts_delegation(&next)
+                {
+                    let _ = tokens.next();
+                    // delegated expression itself may contain `yield from`s too
+                    let rest = rewrite_yield_from(tokens.collect())?;
+                    let (expr, rest) = (|input: ParseStream| {
+                        let expr = input.parse::<Expr>()?;
+                        let rest = input.parse::<TokenStream>()?;
+                        Ok((expr, rest))
+                    })
+                    .parse2(rest)?;
+                    let mac: TokenStream = quote_spanned!(yld.span() => r#yield!(#from #expr));
+                    output.extend(mac);
+                    output.extend(rest);
+                    return Ok(output);
+                }
+                output.extend([TokenTree::Ident(yld)]);
+            }
+            token => output.extend([token]),
+        }
+    }
+    Ok(output)
+}
+
+fn is_std_macro(path: &Path) -> bool {
+    let Some(last) = path.segments.last() else {
+        return false;
+    };
+    if !STD_MACROS.iter().any(|name| last.ident == name) {
+        return false;
+    }
+    match path.segments.len() {
+        1 => path.leading_colon.is_none(),
+        _ => ["std", "core", "alloc"]
+            .iter()
+            .any(|krate| path.segments[0].ident == krate),
+    }
+}
+
+fn is_gn_macro(path: &Path) -> bool {
+    match path.segments.len() {
+        1 => path.leading_colon.is_none() && path.segments[0].ident == "gn",
+        2 => path.segments[0].ident == "yaag" && path.segments[1].ident == "gn",
+        _ => false,
+    }
+}
+
+/// Transforms arguments of an allowed macro invocation.
+///
+/// Returns an error message, if macro is not allowed.
+fn trans_macro(mac: &mut Macro, ctx: &Ctx) -> Result<(), &'static str> {
+    if is_gn_macro(&mac.path) {
+        // nested generator is transformed on it's own
+        return Ok(());
+    }
+    if is_std_macro(&mac.path) {
+        let name = mac.path.segments.last().map(|s| s.ident.to_string());
+        let tokens = match name.as_deref() {
+            Some("matches") => mac.parse_body_with(|input: ParseStream| {
+                let mut expr = input.parse::<Expr>()?;
+                expr.trans(ctx);
+                let rest = input.parse::<TokenStream>()?;
+                Ok(quote!(#expr #rest))
+            }),
+            Some("vec") => mac.parse_body_with(|input: ParseStream| {
+                let fork = input.fork();
+                if fork.parse::<Expr>().is_ok() && fork.peek(Token![;]) {
+                    let mut elem = input.parse::<Expr>()?;
+                    let semi = input.parse::<Token![;]>()?;
+                    let mut len = input.parse::<Expr>()?;
+                    elem.trans(ctx);
+                    len.trans(ctx);
+                    Ok(quote!(#elem #semi #len))
+                } else {
+                    trans_args(input, ctx)
+                }
+            }),
+            _ => mac.parse_body_with(|input: ParseStream| trans_args(input, ctx)),
+        };
+        mac.tokens = tokens.map_err(|_| MACRO_ARGS_ERROR)?;
+        return Ok(());
+    }
+    let path = mac.path.to_token_stream().to_string();
+    if ctx.macros.contains(&path) {
+        // user vouched for this macro, so arguments are only transformed if they look like expressions
+        if let Ok(tokens) = mac.parse_body_with(|input: ParseStream| trans_args(input, ctx)) {
+            mac.tokens = tokens;
+        }
+        return Ok(());
+    }
+    Err(MACRO_ERROR)
+}
+
+fn trans_args(input: ParseStream, ctx: &Ctx) -> syn::Result<TokenStream> {
+    let mut args = Punctuated::<Expr, Token![,]>::parse_terminated(input)?;
+    args.iter_mut().for_each(|arg| arg.trans(ctx));
+    Ok(args.into_token_stream())
+}
+
 macro_rules! assert_no_attr {
     ($e:expr, $self:ident) => {
-        if !$e.attrs.is_empty() {
+        if !are_allowed_attrs(&$e.attrs) {
             *$self = parse_quote_spanned!($e.span() => ::core::compile_error!(#ATTR_ERROR));
             return;
         }
     };
+    (stmt $e:expr, $self:ident) => {
+        if !are_allowed_attrs(&$e.attrs) {
+            *$self = parse_quote_spanned!($e.span() => ::core::compile_error!(#ATTR_ERROR););
+            return;
+        }
+    };
+}
+
+/// Moves (allowed) attributes of the replaced expression onto its replacement, so that `#[cfg]` and such still apply
+fn keep_attrs(attrs: &[Attribute], expr: Expr) -> Expr {
+    if attrs.is_empty() {
+        expr
+    } else {
+        parse_quote!(#(#attrs)* { #expr })
+    }
+}
+
+/// Kind of the hint, declared in the generator header
+#[derive(Clone, Copy)]
+pub enum HintKind {
+    /// `len = n`
+    Len,
+    /// `size_hint = (lo, hi)`
+    SizeHint,
+}
+
+/// Properties of the generator, that are needed to transform it's code
+#[derive(Clone)]
+pub struct Ctx {
+    /// Lent type family, for lending generators
+    pub lending: Option<Type>,
+    /// Synchronous generator is a native coroutine, and `yield` is the real one
+    pub native: bool,
+    /// Resume argument type
+    pub arg: Option<Type>,
+    pub is_async: bool,
+    /// `try gen`: yielded values are wrapped into `Ok`, and `?` yields the error
+    pub is_try: bool,
+    /// `closable gen`: `yield` evaluates to `Result<_, Cancelled>`, see `yaag::Cancelled`
+    pub is_closable: bool,
+    /// Inside of a `try` block, where `?` is left as is
+    pub in_try_block: bool,
+    /// Hint declared in the header, that `size_hint!(..)` updates
+    pub hint: Option<HintKind>,
+    /// User macros, that are allowed inside of the generator
+    pub macros: Vec<String>,
+}
+
+/// Generator's token (see `yaag::sync::Token`), not visible to the generator code
+pub fn token() -> Ident {
+    Ident::new("token", Span::mixed_site())
+}
+
+impl Ctx {
+    pub fn module(&self) -> Ident {
+        if self.is_async {
+            parse_quote!(not_sync)
+        } else {
+            parse_quote!(sync)
+        }
+    }
+
+    /// Expression yielding `expr` and evaluating to the next resume argument
+    pub fn yield_expr(&self, expr: &Expr, span: Span) -> Expr {
+        if self.is_try {
+            self.raw_yield_expr(
+                &parse_quote_spanned! { span => ::core::result::Result::Ok(#expr) },
+                span,
+            )
+        } else {
+            self.raw_yield_expr(expr, span)
+        }
+    }
+
+    /// Yields `expr` as-is, even in `try gen`
+    fn raw_yield_expr(&self, expr: &Expr, span: Span) -> Expr {
+        let token = token();
+        if self.lending.is_some() {
+            parse_quote_spanned! {span => ::yaag::sync::LendYield::___make(&#token, #expr).await }
+        } else if self.native {
+            parse_quote_spanned! {span => yield #expr }
+        } else if self.is_closable {
+            let module = self.module();
+            parse_quote_spanned! {span => ::yaag::#module::ClosableYield::___make(&#token, #expr).await }
+        } else if self.is_async {
+            parse_quote_spanned! {span => ::yaag::not_sync::Yield::___make(&#token, #expr).await }
+        } else {
+            parse_quote_spanned! {span => ::yaag::sync::Yield::___make(&#token, #expr).await }
+        }
+    }
+
+    /// Expression handing the hint re-declared by `expr` to the generator
+    pub fn set_hint_expr(&self, expr: &Expr, span: Span) -> Expr {
+        let hint = Ident::new("hint", Span::mixed_site());
+        let hint_expr: Expr = match self.hint {
+            None => {
+                return parse_quote_spanned! { span => ::core::compile_error!(#SIZE_HINT_UNDECLARED_ERROR) };
+            }
+            Some(HintKind::Len) => parse_quote_spanned! { span => {
+                let #hint: usize = #expr;
+                (#hint, ::core::option::Option::Some(#hint))
+            }},
+            Some(HintKind::SizeHint) => parse_quote_spanned! { span => {
+                let #hint: (usize, ::core::option::Option<usize>) = #expr;
+                #hint
+            }},
+        };
+        let token = token();
+        let module = self.module();
+        parse_quote_spanned! { span => ::yaag::#module::SetHint::___make(&#token, #hint_expr).await }
+    }
+
+    /// Expression evaluating to the first resume argument
+    pub fn first_arg_expr(&self) -> Expr {
+        let token = token();
+        parse_quote! { ::yaag::sync::Yield::___first(&#token).await }
+    }
+
+    /// Resume argument type (`()` if there's none)
+    pub fn arg_ty(&self) -> Type {
+        self.arg.clone().unwrap_or_else(|| parse_quote!(()))
+    }
+
+    /// Expression awaiting for `fut` with the real waker
+    pub fn await_expr(&self, fut: &Expr, span: Span) -> Expr {
+        let token = token();
+        parse_quote_spanned! { span => ::yaag::not_sync::Await::___make(&#token, #fut).await }
+    }
+
+    /// Expression yielding everything produced by `expr`, and evaluating to it's completion value
+    ///
+    /// In `closable gen`, it's `Result<_, Cancelled>` instead, and the delegate is dropped once the generator is closed.
+    pub fn yield_from_expr(&self, expr: &Expr, span: Span) -> Expr {
+        if self.arg.is_some() {
+            return parse_quote_spanned! { span => ::core::compile_error!(#YIELD_FROM_ARG_ERROR) };
+        }
+        if self.lending.is_some() {
+            return parse_quote_spanned! { span => ::core::compile_error!(#YIELD_FROM_LENDING_ERROR) };
+        }
+        let delegate = Ident::new("delegate", Span::mixed_site());
+        let item = Ident::new("item", Span::mixed_site());
+        let ret = Ident::new("ret", Span::mixed_site());
+        // delegate's items are yielded as they are, even in `try gen`
+        let yld = self.raw_yield_expr(&parse_quote!(#item), span);
+        let (delegate_init, resume): (Expr, Expr) = if self.is_async {
+            let resume = self.await_expr(
+                &parse_quote!(::yaag::not_sync::Resume::new(#delegate.as_mut())),
+                span,
+            );
+            (
+                parse_quote_spanned! { span => {
+                    #[allow(unused_imports)]
+                    use ::yaag::not_sync::{ViaDelegate as _, ViaGn as _, ViaIter as _, ViaStream as _};
+                    (&&&&::yaag::not_sync::Wrap::new(#expr)).___delegate()
+                }},
+                resume,
+            )
+        } else {
+            (
+                parse_quote_spanned! { span => {
+                    #[allow(unused_imports)]
+                    use ::yaag::sync::{ViaGn as _, ViaIter as _};
+                    (&&::yaag::sync::Wrap::new(#expr)).___delegate()
+                }},
+                parse_quote_spanned! { span => ::yaag::Generator::resume(#delegate.as_mut()) },
+            )
+        };
+        if self.is_closable {
+            let cancelled = Ident::new("cancelled", Span::mixed_site());
+            return parse_quote_spanned! { span => {
+                let mut #delegate = ::core::pin::pin!(#delegate_init);
+                loop {
+                    match #resume {
+                        ::yaag::GeneratorState::Yielded(#item) => {
+                            if let ::core::result::Result::Err(#cancelled) = #yld {
+                                break ::core::result::Result::Err(#cancelled);
+                            }
+                        }
+                        ::yaag::GeneratorState::Complete(#ret) => {
+                            break ::core::result::Result::Ok(#ret);
+                        }
+                    }
+                }
+            }};
+        }
+        parse_quote_spanned! { span => {
+            let mut #delegate = ::core::pin::pin!(#delegate_init);
+            loop {
+                match #resume {
+                    ::yaag::GeneratorState::Yielded(#item) => {
+                        #yld;
+                    }
+                    ::yaag::GeneratorState::Complete(#ret) => break #ret,
+                }
+            }
+        }}
+    }
+}
+
+impl Ctx {
+    /// `expr?` in `try gen`: yields the error and completes the generator
+    pub fn try_expr(&self, expr: &Expr, span: Span) -> Expr {
+        let value = Ident::new("value", Span::mixed_site());
+        let err = Ident::new("err", Span::mixed_site());
+        let mut yld = self.raw_yield_expr(
+            &parse_quote_spanned! { span => ::core::result::Result::Err(#err) },
+            span,
+        );
+        if self.is_closable {
+            // generator returns either way, even if it's closed
+            yld = parse_quote!(let _ = #yld);
+        }
+        // error is the last item, whatever the header declared
+        let last = self.hint.is_some().then(|| {
+            let token = token();
+            let module = self.module();
+            quote_spanned! { span =>
+                ::yaag::#module::SetHint::___make(&#token, (1, ::core::option::Option::Some(1))).await;
+            }
+        });
+        parse_quote_spanned! { span => match #expr {
+            ::core::result::Result::Ok(#value) => #value,
+            ::core::result::Result::Err(#err) => {
+                // error type is the same most of the time
+                #[allow(clippy::useless_conversion)]
+                let #err = ::core::convert::From::from(#err);
+                #last
+                #yld;
+                return;
+            }
+        }}
+    }
 }
 
 pub trait Trans: Sized {
-    fn trans(&mut self, _out: &Type, _is_async: bool) {}
+    fn trans(&mut self, _ctx: &Ctx) {}
 }
 
 impl<T: Trans> Trans for Option<T> {
-    fn trans(&mut self, out: &Type, is_async: bool) {
+    fn trans(&mut self, ctx: &Ctx) {
         if let Some(s) = self {
-            s.trans(out, is_async);
+            s.trans(ctx);
         }
     }
 }
 
 impl<T: Trans> Trans for Box<T> {
-    fn trans(&mut self, out: &Type, is_async: bool) {
-        T::trans(&mut **self, out, is_async);
+    fn trans(&mut self, ctx: &Ctx) {
+        T::trans(&mut **self, ctx);
     }
 }
 
 impl Trans for Block {
-    fn trans(&mut self, out: &Type, is_async: bool) {
-        self.stmts
-            .iter_mut()
-            .for_each(|stmt| stmt.trans(out, is_async));
+    fn trans(&mut self, ctx: &Ctx) {
+        self.stmts.iter_mut().for_each(|stmt| stmt.trans(ctx));
     }
 }
 
 impl Trans for Stmt {
-    fn trans(&mut self, out: &Type, is_async: bool) {
+    fn trans(&mut self, ctx: &Ctx) {
         match self {
             Stmt::Local(local) => {
-                assert_no_attr!(local, self);
-                local.trans(out, is_async);
+                assert_no_attr!(stmt local, self);
+                local.trans(ctx);
+            }
+            // items are not a part of the generator, but can't `yield` either
+            Stmt::Item(item) => {
+                if let Some(span) = find_yield(item.to_token_stream()) {
+                    *self = parse_quote_spanned! { span => ::core::compile_error!(#NESTED_YIELD_ERROR); };
+                }
             }
-            // ignore items
-            Stmt::Item(_item) => {}
-            Stmt::Expr(expr, _semi) => expr.trans(out, is_async),
+            Stmt::Expr(expr, _semi) => expr.trans(ctx),
             Stmt::Macro(stmt_macro) => {
-                *self = parse_quote_spanned! { stmt_macro.span() => ::core::compile_error!(#MACRO_ERROR); };
+                assert_no_attr!(stmt stmt_macro, self);
+                if let Some(expr) = yield_macro(&stmt_macro.mac, ctx)
+                    .or_else(|| size_hint_macro(&stmt_macro.mac, ctx))
+                {
+                    *self = Stmt::Expr(keep_attrs(&stmt_macro.attrs, expr), stmt_macro.semi_token);
+                } else if let Err(err) = trans_macro(&mut stmt_macro.mac, ctx) {
+                    *self =
+                        parse_quote_spanned! { stmt_macro.span() => ::core::compile_error!(#err); };
+                }
             }
         }
     }
 }
 
 impl Trans for Item {
-    fn trans(&mut self, _out: &Type, _is_async: bool) {
+    fn trans(&mut self, _ctx: &Ctx) {
         match self {
             Item::Const(item_const) => assert_no_attr!(item_const, self),
             Item::Enum(item_enum) => assert_no_attr!(item_enum, self),
@@ -87,17 +681,19 @@ impl Trans for Item {
             Item::Union(item_union) => assert_no_attr!(item_union, self),
             Item::Use(item_use) => assert_no_attr!(item_use, self),
             Item::Verbatim(_token_stream) => {}
-            _ => unreachable!(),
+            _ => {
+                *self = parse_quote_spanned! { self.span() => ::core::compile_error!(#UNSUPPORTED_ERROR); };
+            }
         }
     }
 }
 
 impl Trans for Local {
-    fn trans(&mut self, out: &Type, is_async: bool) {
+    fn trans(&mut self, ctx: &Ctx) {
         if let Some(init) = &mut self.init {
-            init.expr.trans(out, is_async);
+            init.expr.trans(ctx);
             if let Some((_, diverge)) = &mut init.diverge {
-                diverge.trans(out, is_async);
+                diverge.trans(ctx);
             }
         }
     }
@@ -105,223 +701,248 @@ impl Trans for Local {
 
 impl Trans for Expr {
     #[allow(clippy::too_many_lines)]
-    fn trans(&mut self, out: &Type, is_async: bool) {
+    fn trans(&mut self, ctx: &Ctx) {
         match self {
             Expr::Array(expr_array) => {
                 assert_no_attr!(expr_array, self);
-                expr_array
-                    .elems
-                    .iter_mut()
-                    .for_each(|el| el.trans(out, is_async));
+                expr_array.elems.iter_mut().for_each(|el| el.trans(ctx));
             }
             Expr::Assign(expr_assign) => {
                 assert_no_attr!(expr_assign, self);
-                expr_assign.right.trans(out, is_async);
+                expr_assign.left.trans(ctx);
+                expr_assign.right.trans(ctx);
             }
-            // async blocks are OK, but still no attrs allowed
+            // async blocks are OK as long as they do not yield, but still no attrs allowed
             Expr::Async(expr_async) => {
                 assert_no_attr!(expr_async, self);
+                if let Some(span) = find_yield(expr_async.block.to_token_stream()) {
+                    *self = parse_quote_spanned! { span => ::core::compile_error!(#NESTED_YIELD_ERROR) };
+                }
             }
             Expr::Binary(expr_binary) => {
                 assert_no_attr!(expr_binary, self);
-                expr_binary.left.trans(out, is_async);
-                expr_binary.right.trans(out, is_async);
+                expr_binary.left.trans(ctx);
+                expr_binary.right.trans(ctx);
             }
             Expr::Block(expr_block) => {
                 assert_no_attr!(expr_block, self);
-                expr_block.block.trans(out, is_async);
+                expr_block.block.trans(ctx);
             }
             Expr::Break(expr_break) => {
                 assert_no_attr!(expr_break, self);
                 if let Some(expr) = &mut expr_break.expr {
-                    expr.trans(out, is_async);
+                    expr.trans(ctx);
                 }
             }
             Expr::Call(expr_call) => {
                 assert_no_attr!(expr_call, self);
-                expr_call.func.trans(out, is_async);
-                expr_call
-                    .args
-                    .iter_mut()
-                    .for_each(|arg| arg.trans(out, is_async));
+                expr_call.func.trans(ctx);
+                expr_call.args.iter_mut().for_each(|arg| arg.trans(ctx));
             }
             Expr::Cast(expr_cast) => {
                 assert_no_attr!(expr_cast, self);
-                expr_cast.expr.trans(out, is_async);
+                expr_cast.expr.trans(ctx);
             }
-            // closures can be ignored, but no attrs allowed
+            // closures can be ignored as long as they do not yield, but no attrs allowed
             Expr::Closure(expr_closure) => {
                 assert_no_attr!(expr_closure, self);
+                if let Some(span) = find_yield(expr_closure.body.to_token_stream()) {
+                    *self = parse_quote_spanned! { span => ::core::compile_error!(#NESTED_YIELD_ERROR) };
+                }
             }
             // const blocks can't have `await`s and/or `yield`s YET. that would be posible, once const-traits arrove
             Expr::Const(expr_const) => {
                 assert_no_attr!(expr_const, self);
-                expr_const.block.trans(out, is_async);
+                expr_const.block.trans(ctx);
             }
             Expr::Continue(expr_continue) => {
                 assert_no_attr!(expr_continue, self);
             }
             Expr::Field(expr_field) => {
                 assert_no_attr!(expr_field, self);
-                expr_field.base.trans(out, is_async);
+                expr_field.base.trans(ctx);
             }
             Expr::ForLoop(expr_for_loop) => {
                 assert_no_attr!(expr_for_loop, self);
-                expr_for_loop.expr.trans(out, is_async);
-                expr_for_loop.body.trans(out, is_async);
+                expr_for_loop.expr.trans(ctx);
+                expr_for_loop.body.trans(ctx);
             }
             Expr::Group(expr_group) => {
                 assert_no_attr!(expr_group, self);
-                expr_group.expr.trans(out, is_async);
+                expr_group.expr.trans(ctx);
             }
             Expr::If(expr_if) => {
                 assert_no_attr!(expr_if, self);
-                expr_if.cond.trans(out, is_async);
-                expr_if.then_branch.trans(out, is_async);
+                expr_if.cond.trans(ctx);
+                expr_if.then_branch.trans(ctx);
                 if let Some((_, else_branch)) = &mut expr_if.else_branch {
-                    else_branch.trans(out, is_async);
+                    else_branch.trans(ctx);
                 }
             }
             Expr::Index(expr_index) => {
                 assert_no_attr!(expr_index, self);
-                expr_index.expr.trans(out, is_async);
-                expr_index.index.trans(out, is_async);
+                expr_index.expr.trans(ctx);
+                expr_index.index.trans(ctx);
             }
             Expr::Infer(expr_infer) => {
                 assert_no_attr!(expr_infer, self);
             }
             Expr::Let(expr_let) => {
                 assert_no_attr!(expr_let, self);
-                expr_let.expr.trans(out, is_async);
+                expr_let.expr.trans(ctx);
             }
             Expr::Lit(expr_lit) => {
                 assert_no_attr!(expr_lit, self);
             }
             Expr::Loop(expr_loop) => {
                 assert_no_attr!(expr_loop, self);
-                expr_loop.body.trans(out, is_async);
+                expr_loop.body.trans(ctx);
             }
             Expr::Macro(expr_macro) => {
-                *self = parse_quote_spanned! { expr_macro.span() => ::core::compile_error!(#MACRO_ERROR) }
+                assert_no_attr!(expr_macro, self);
+                if let Some(expr) = yield_macro(&expr_macro.mac, ctx)
+                    .or_else(|| size_hint_macro(&expr_macro.mac, ctx))
+                {
+                    *self = keep_attrs(&expr_macro.attrs, expr);
+                } else if let Err(err) = trans_macro(&mut expr_macro.mac, ctx) {
+                    *self =
+                        parse_quote_spanned! { expr_macro.span() => ::core::compile_error!(#err) }
+                }
             }
             Expr::Match(expr_match) => {
                 assert_no_attr!(expr_match, self);
-                expr_match.expr.trans(out, is_async);
-                expr_match
-                    .arms
-                    .iter_mut()
-                    .for_each(|arm| arm.trans(out, is_async));
+                expr_match.expr.trans(ctx);
+                expr_match.arms.iter_mut().for_each(|arm| arm.trans(ctx));
             }
             Expr::MethodCall(expr_method_call) => {
                 assert_no_attr!(expr_method_call, self);
-                expr_method_call.receiver.trans(out, is_async);
+                expr_method_call.receiver.trans(ctx);
                 expr_method_call
                     .args
                     .iter_mut()
-                    .for_each(|arg| arg.trans(out, is_async));
+                    .for_each(|arg| arg.trans(ctx));
             }
             Expr::Paren(expr_paren) => {
                 assert_no_attr!(expr_paren, self);
-                expr_paren.expr.trans(out, is_async);
+                let is_yield = matches!(*expr_paren.expr, Expr::Yield(_));
+                expr_paren.expr.trans(ctx);
+                // `(yield x)` becomes a method call, parentheses of which trip `unused_parens`
+                if is_yield && !ctx.native {
+                    *self = Expr::clone(&expr_paren.expr);
+                }
             }
             Expr::Path(expr_path) => assert_no_attr!(expr_path, self),
             Expr::Range(expr_range) => {
                 assert_no_attr!(expr_range, self);
-                expr_range.start.trans(out, is_async);
-                expr_range.end.trans(out, is_async);
+                expr_range.start.trans(ctx);
+                expr_range.end.trans(ctx);
             }
             Expr::RawAddr(expr_raw_addr) => {
                 assert_no_attr!(expr_raw_addr, self);
-                expr_raw_addr.expr.trans(out, is_async);
+                expr_raw_addr.expr.trans(ctx);
             }
             Expr::Reference(expr_reference) => {
                 assert_no_attr!(expr_reference, self);
-                expr_reference.expr.trans(out, is_async);
+                expr_reference.expr.trans(ctx);
             }
             Expr::Repeat(expr_repeat) => {
                 assert_no_attr!(expr_repeat, self);
-                expr_repeat.expr.trans(out, is_async);
+                expr_repeat.expr.trans(ctx);
             }
             Expr::Return(expr_return) => {
                 assert_no_attr!(expr_return, self);
-                expr_return.expr.trans(out, is_async);
+                expr_return.expr.trans(ctx);
             }
             Expr::Struct(expr_struct) => {
                 assert_no_attr!(expr_struct, self);
-                expr_struct
-                    .fields
-                    .iter_mut()
-                    .for_each(|f| f.trans(out, is_async));
-                expr_struct.rest.trans(out, is_async);
+                expr_struct.fields.iter_mut().for_each(|f| f.trans(ctx));
+                expr_struct.rest.trans(ctx);
             }
             Expr::Try(expr_try) => {
                 assert_no_attr!(expr_try, self);
-                expr_try.expr.trans(out, is_async);
+                expr_try.expr.trans(ctx);
+                if ctx.is_try && !ctx.in_try_block {
+                    *self = keep_attrs(
+                        &expr_try.attrs,
+                        ctx.try_expr(&expr_try.expr, expr_try.span()),
+                    );
+                }
             }
             Expr::TryBlock(expr_try_block) => {
                 assert_no_attr!(expr_try_block, self);
-                expr_try_block.block.trans(out, is_async);
+                // `?` belongs to the `try` block here
+                let ctx = Ctx {
+                    in_try_block: true,
+                    ..ctx.clone()
+                };
+                expr_try_block.block.trans(&ctx);
             }
             Expr::Tuple(expr_tuple) => {
                 assert_no_attr!(expr_tuple, self);
-                expr_tuple
-                    .elems
-                    .iter_mut()
-                    .for_each(|el| el.trans(out, is_async));
+                expr_tuple.elems.iter_mut().for_each(|el| el.trans(ctx));
             }
             Expr::Unary(expr_unary) => {
                 assert_no_attr!(expr_unary, self);
-                expr_unary.expr.trans(out, is_async);
+                expr_unary.expr.trans(ctx);
             }
             Expr::Unsafe(expr_unsafe) => {
                 assert_no_attr!(expr_unsafe, self);
-                expr_unsafe.block.trans(out, is_async);
+                expr_unsafe.block.trans(ctx);
             }
             Expr::Verbatim(token_stream) => {
                 *self = parse_quote_spanned! {token_stream.span() => ::core::compile_error!("Could not parse this, please remove")}
             }
             Expr::While(expr_while) => {
                 assert_no_attr!(expr_while, self);
-                expr_while.cond.trans(out, is_async);
-                expr_while.body.trans(out, is_async);
+                expr_while.cond.trans(ctx);
+                expr_while.body.trans(ctx);
             }
             Expr::Await(expr_await) => {
                 assert_no_attr!(expr_await, self);
-                if is_async {
-                    let fut = &expr_await.base;
-                    expr_await.base = parse_quote_spanned! { expr_await.span() => unsafe { ::yaag::not_sync::Await::<_, #out>::___make(#fut) } }
+                expr_await.base.trans(ctx);
+                if ctx.is_async {
+                    *self = keep_attrs(
+                        &expr_await.attrs,
+                        ctx.await_expr(&expr_await.base, expr_await.span()),
+                    );
                 } else {
                     *self = parse_quote_spanned! { expr_await.span() => ::core::compile_error!(#AWAIT_ERROR) }
                 }
             }
             Expr::Yield(expr_yield) => {
+                assert_no_attr!(expr_yield, self);
                 let span = expr_yield.span();
                 let expr = &mut expr_yield.expr;
-                expr.trans(out, is_async);
-                let module: Ident = if is_async {
-                    parse_quote!(not_sync)
-                } else {
-                    parse_quote!(sync)
-                };
-                *self = parse_quote_spanned! {span => unsafe { ::yaag::#module::Yield::<#out>::___make(#expr) }.await };
+                expr.trans(ctx);
+                let expr = expr.as_deref().cloned().unwrap_or_else(|| parse_quote!(()));
+                *self = keep_attrs(&expr_yield.attrs, ctx.yield_expr(&expr, span));
+            }
+            _ => {
+                *self = parse_quote_spanned! { self.span() => ::core::compile_error!(#UNSUPPORTED_ERROR) };
             }
-            _ => todo!(),
         }
     }
 }
 
 impl Trans for Arm {
-    fn trans(&mut self, _out: &Type, _is_async: bool) {
-        if !self.attrs.is_empty() {
+    fn trans(&mut self, ctx: &Ctx) {
+        if !are_allowed_attrs(&self.attrs) {
             *self = parse_quote_spanned!(self.span() =>  _ => ::core::compile_error!(#ATTR_ERROR));
+            return;
         }
+        if let Some((_, guard)) = &mut self.guard {
+            guard.trans(ctx);
+        }
+        self.body.trans(ctx);
     }
 }
 
 impl Trans for FieldValue {
-    fn trans(&mut self, _out: &Type, _is_async: bool) {
-        if !self.attrs.is_empty() {
+    fn trans(&mut self, ctx: &Ctx) {
+        if !are_allowed_attrs(&self.attrs) {
             self.expr = parse_quote_spanned!(self.span() =>  ::core::compile_error!(#ATTR_ERROR));
+            return;
         }
+        self.expr.trans(ctx);
     }
 }